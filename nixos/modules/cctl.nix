@@ -82,6 +82,15 @@ in
             type = types.nullOr types.attrs;
             description = "The runtime arguments expected by this contract.";
           };
+          deployer = mkOption {
+            default = null;
+            type = types.nullOr types.attrs;
+            example = { user = 2; };
+            description = ''
+              The account that signs the deploy, either one of the generated cctl users `{ user = 2; }`
+              or a secret key file `{ secret_key = "/path/to/secret_key.pem"; }`. Defaults to user-1.
+            '';
+          };
         };
       }));
     };
@@ -0,0 +1,114 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use casper_types::{account::AccountHash, PublicKey, SecretKey};
+
+use super::CctlError;
+
+/// One of the funded test accounts cctl generates under `assets/users/user-<index>`
+#[derive(Debug)]
+pub struct CctlUser {
+    pub index: u8,
+    pub keys: AccountKeys,
+}
+
+impl CctlUser {
+    pub(crate) fn load(assets_dir: &Path, index: u8) -> Result<Self, CctlError> {
+        let user_dir = users_dir(assets_dir).join(format!("user-{index}"));
+        if !user_dir.is_dir() {
+            return Err(CctlError::UserNotFound { index });
+        }
+        Ok(CctlUser {
+            index,
+            keys: AccountKeys::load(&user_dir)?,
+        })
+    }
+}
+
+/// A key pair cctl generated, e.g. for a node's validator or the faucet
+#[derive(Debug)]
+pub struct AccountKeys {
+    pub secret_key: SecretKey,
+    pub public_key: PublicKey,
+    pub account_hash: AccountHash,
+}
+
+impl AccountKeys {
+    /// Loads the `secret_key.pem` and `public_key.pem` located in `keys_dir`
+    pub(crate) fn load(keys_dir: &Path) -> Result<Self, CctlError> {
+        let secret_key = load_secret_key(&keys_dir.join("secret_key.pem"))?;
+        let public_key = load_public_key(&keys_dir.join("public_key.pem"))?;
+        Ok(AccountKeys {
+            account_hash: public_key.to_account_hash(),
+            secret_key,
+            public_key,
+        })
+    }
+}
+
+/// The directory of the validator keys of the node with the given id
+pub(crate) fn node_keys_dir(assets_dir: &Path, node_id: u8) -> PathBuf {
+    assets_dir.join(format!("nodes/node-{node_id}/keys"))
+}
+
+/// The directory of the faucet account's keys
+pub(crate) fn faucet_keys_dir(assets_dir: &Path) -> PathBuf {
+    assets_dir.join("faucet")
+}
+
+fn users_dir(assets_dir: &Path) -> PathBuf {
+    assets_dir.join("users")
+}
+
+/// The indices of all users in the assets directory, in ascending order
+pub(crate) fn user_indices(assets_dir: &Path) -> Result<Vec<u8>, CctlError> {
+    let mut indices = fs::read_dir(users_dir(assets_dir))?
+        .map(|entry| Ok(entry?.file_name()))
+        .collect::<Result<Vec<_>, CctlError>>()?
+        .into_iter()
+        .filter_map(|name| name.to_str()?.strip_prefix("user-")?.parse().ok())
+        .collect::<Vec<u8>>();
+    indices.sort_unstable();
+    Ok(indices)
+}
+
+pub(crate) fn load_secret_key(path: &Path) -> Result<SecretKey, CctlError> {
+    SecretKey::from_file(path).map_err(|err| CctlError::Key {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+pub(crate) fn load_public_key(path: &Path) -> Result<PublicKey, CctlError> {
+    PublicKey::from_file(path).map_err(|err| CctlError::Key {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_user_indices() {
+        let assets_dir = tempdir().unwrap();
+        for user_dir in ["user-10", "user-2", "user-1", "faucet", "user-x"] {
+            fs::create_dir_all(assets_dir.path().join("users").join(user_dir)).unwrap();
+        }
+
+        assert_eq!(vec![1, 2, 10], user_indices(assets_dir.path()).unwrap());
+    }
+
+    #[test]
+    fn test_load_unknown_user() {
+        let assets_dir = tempdir().unwrap();
+        fs::create_dir_all(assets_dir.path().join("users/user-1")).unwrap();
+
+        assert!(matches!(
+            CctlUser::load(assets_dir.path(), 2),
+            Err(CctlError::UserNotFound { index: 2 })
+        ));
+    }
+}
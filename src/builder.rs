@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{
+    CCTLNetwork, CctlCommandRunner, CctlError, ChainspecOverrides, DeployableContract, Deployer,
+    GenesisAccount, NetworkProfile, NodeConfigOverrides, ShellCommandRunner, Topology,
+    DEFAULT_DEPLOY_TIMEOUT, DEFAULT_READINESS_TIMEOUT, DEFAULT_SCRIPT_TIMEOUT,
+};
+
+/// Configures and spins up a [`CCTLNetwork`]
+///
+/// ```no_run
+/// # async fn example() -> Result<(), cctl::CctlError> {
+/// use cctl::{CCTLNetwork, Deployer};
+///
+/// let network = CCTLNetwork::builder()
+///     .with_working_dir("/tmp/cctl")
+///     .with_deployer(Deployer::User(2))
+///     .run()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CCTLNetworkBuilder {
+    pub(crate) working_dir: Option<PathBuf>,
+    pub(crate) contracts: Vec<DeployableContract>,
+    pub(crate) chainspec_path: Option<PathBuf>,
+    pub(crate) chainspec_overrides: ChainspecOverrides,
+    pub(crate) node_config_path: Option<PathBuf>,
+    pub(crate) node_config_overrides: NodeConfigOverrides,
+    pub(crate) node_specific_config_overrides: BTreeMap<u8, NodeConfigOverrides>,
+    pub(crate) chain_name: Option<String>,
+    pub(crate) genesis_accounts: Vec<GenesisAccount>,
+    pub(crate) topology: Option<Topology>,
+    pub(crate) profile: NetworkProfile,
+    pub(crate) deployer: Deployer,
+    pub(crate) deploy_timeout: Duration,
+    pub(crate) script_timeout: Duration,
+    pub(crate) readiness_probe: bool,
+    pub(crate) await_validating: bool,
+    pub(crate) readiness_timeout: Duration,
+    pub(crate) runner: Arc<dyn CctlCommandRunner>,
+    pub(crate) keep_working_dir: bool,
+}
+
+impl Default for CCTLNetworkBuilder {
+    fn default() -> Self {
+        Self {
+            working_dir: None,
+            contracts: Vec::new(),
+            chainspec_path: env::var("CCTL_CASPER_CHAINSPEC").ok().map(PathBuf::from),
+            chainspec_overrides: ChainspecOverrides::default(),
+            node_config_path: env::var("CCTL_CASPER_NODE_CONFIG").ok().map(PathBuf::from),
+            node_config_overrides: NodeConfigOverrides::default(),
+            node_specific_config_overrides: BTreeMap::new(),
+            chain_name: None,
+            genesis_accounts: Vec::new(),
+            topology: None,
+            profile: NetworkProfile::default(),
+            deployer: Deployer::default(),
+            deploy_timeout: DEFAULT_DEPLOY_TIMEOUT,
+            script_timeout: DEFAULT_SCRIPT_TIMEOUT,
+            readiness_probe: true,
+            await_validating: true,
+            readiness_timeout: DEFAULT_READINESS_TIMEOUT,
+            runner: Arc::new(ShellCommandRunner),
+            keep_working_dir: false,
+        }
+    }
+}
+
+impl CCTLNetworkBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The directory cctl puts its assets and the deployed contract hashes into.
+    /// A temporary directory is used if none is provided, which is removed on teardown.
+    pub fn with_working_dir(mut self, working_dir: impl Into<PathBuf>) -> Self {
+        self.working_dir = Some(working_dir.into());
+        self
+    }
+
+    /// Keeps the temporary working directory on teardown for post-mortem debugging.
+    /// It is always kept if the startup fails or the network is dropped while panicking.
+    pub fn with_keep_working_dir(mut self, keep_working_dir: bool) -> Self {
+        self.keep_working_dir = keep_working_dir;
+        self
+    }
+
+    /// Adds a contract that is deployed once the network is up and processing blocks
+    pub fn with_contract(mut self, contract: DeployableContract) -> Self {
+        self.contracts.push(contract);
+        self
+    }
+
+    /// Adds contracts that are deployed, in order, once the network is up and processing blocks
+    pub fn with_contracts(
+        mut self,
+        contracts: impl IntoIterator<Item = DeployableContract>,
+    ) -> Self {
+        self.contracts.extend(contracts);
+        self
+    }
+
+    /// The chainspec.toml passed to `cctl-infra-net-setup`. Defaults to the `CCTL_CASPER_CHAINSPEC`
+    /// environment variable if set, cctl's default is used otherwise
+    pub fn with_chainspec_path(mut self, chainspec_path: impl Into<PathBuf>) -> Self {
+        self.chainspec_path = Some(chainspec_path.into());
+        self
+    }
+
+    /// Changes applied to the chainspec passed with [`with_chainspec_path`](Self::with_chainspec_path)
+    /// or otherwise cctl's default one, found through the `CCTL_CASPER_HOME` environment variable.
+    /// The result is written to `chainspec.toml` in the working directory and passed to
+    /// `cctl-infra-net-setup`. Overrides of later calls take precedence.
+    pub fn with_chainspec_overrides(mut self, chainspec_overrides: ChainspecOverrides) -> Self {
+        self.chainspec_overrides.extend(chainspec_overrides);
+        self
+    }
+
+    /// The casper node config.toml passed to `cctl-infra-net-setup`. Defaults to the
+    /// `CCTL_CASPER_NODE_CONFIG` environment variable if set, cctl's default is used otherwise
+    pub fn with_node_config_path(mut self, node_config_path: impl Into<PathBuf>) -> Self {
+        self.node_config_path = Some(node_config_path.into());
+        self
+    }
+
+    /// Changes applied to the config.toml of every node after `cctl-infra-net-setup` generated them
+    pub fn with_node_config_overrides(
+        mut self,
+        node_config_overrides: NodeConfigOverrides,
+    ) -> Self {
+        self.node_config_overrides.extend(node_config_overrides);
+        self
+    }
+
+    /// Changes applied to the config.toml of a single node, after those for all nodes
+    pub fn with_node_config_overrides_for(
+        mut self,
+        node_id: u8,
+        node_config_overrides: NodeConfigOverrides,
+    ) -> Self {
+        self.node_specific_config_overrides
+            .entry(node_id)
+            .or_default()
+            .extend(node_config_overrides);
+        self
+    }
+
+    /// Overrides the chain name used when submitting deploys, which is otherwise read from
+    /// the `network.name` of the chainspec the network was set up with
+    pub fn with_chain_name(mut self, chain_name: impl Into<String>) -> Self {
+        self.chain_name = Some(chain_name.into());
+        self
+    }
+
+    /// Adds an account to the ones cctl funds at genesis
+    pub fn with_genesis_account(mut self, genesis_account: GenesisAccount) -> Self {
+        self.genesis_accounts.push(genesis_account);
+        self
+    }
+
+    /// Adds accounts to the ones cctl funds at genesis
+    pub fn with_genesis_accounts(
+        mut self,
+        genesis_accounts: impl IntoIterator<Item = GenesisAccount>,
+    ) -> Self {
+        self.genesis_accounts.extend(genesis_accounts);
+        self
+    }
+
+    /// The number of nodes and which of them start at genesis, cctl's default topology is used otherwise.
+    /// Starting the network fails with [`CctlError::TopologyMismatch`] if cctl set up other nodes.
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = Some(topology);
+        self
+    }
+
+    /// The preset the network is configured with, e.g. [`NetworkProfile::Fast`] for quick
+    /// test runs. Chainspec overrides and the topology configured on the builder take precedence.
+    pub fn with_profile(mut self, profile: NetworkProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// The account that signs the contract deploys which don't specify their own deployer,
+    /// defaults to cctl's `user-1`
+    pub fn with_deployer(mut self, deployer: Deployer) -> Self {
+        self.deployer = deployer;
+        self
+    }
+
+    /// The maximum time to wait for each deployed contract to be executed
+    pub fn with_deploy_timeout(mut self, deploy_timeout: Duration) -> Self {
+        self.deploy_timeout = deploy_timeout;
+        self
+    }
+
+    /// The maximum time each cctl script may run before it is killed and startup fails.
+    /// With the readiness probe enabled, the started network also has this long to produce block 1.
+    pub fn with_script_timeout(mut self, script_timeout: Duration) -> Self {
+        self.script_timeout = script_timeout;
+        self
+    }
+
+    /// Whether to wait for block 1 and for the running nodes and sidecars to answer requests
+    /// before the network is handed over, enabled by default. Disable it when replaying cctl
+    /// scripts. With the probe enabled, the startup also waits for the genesis validators to
+    /// validate, see [`with_await_validating`](Self::with_await_validating).
+    pub fn with_readiness_probe(mut self, readiness_probe: bool) -> Self {
+        self.readiness_probe = readiness_probe;
+        self
+    }
+
+    /// Whether the readiness probe also waits until the reactor of every running node of
+    /// validator groups 1 and 2, i.e. cctl's genesis validators, reached the `Validate` state.
+    /// Enabled by default. Disable it if the configured genesis accounts or chainspec keep
+    /// some of those nodes from validating.
+    pub fn with_await_validating(mut self, await_validating: bool) -> Self {
+        self.await_validating = await_validating;
+        self
+    }
+
+    /// The maximum time the running nodes and sidecars have to become ready once block 1 was produced
+    pub fn with_readiness_timeout(mut self, readiness_timeout: Duration) -> Self {
+        self.readiness_timeout = readiness_timeout;
+        self
+    }
+
+    /// Executes the cctl scripts, defaults to the [`ShellCommandRunner`].
+    /// A [`ReplayCommandRunner`](crate::ReplayCommandRunner) allows running without a cctl installation.
+    pub fn with_command_runner(mut self, runner: Arc<dyn CctlCommandRunner>) -> Self {
+        self.runner = runner;
+        self
+    }
+
+    /// Spins up the configured CCTL network, and deploys the contracts if any were provided
+    ///
+    /// WARNING: with the default [`ShellCommandRunner`], do not use this function in unit tests,
+    /// only sequentially executed integration tests. Ensure that two instances of this function
+    /// are not running at the same time even in different processes. A network replayed through
+    /// a [`ReplayCommandRunner`](crate::ReplayCommandRunner) doesn't share these restrictions.
+    pub async fn run(self) -> Result<CCTLNetwork, CctlError> {
+        CCTLNetwork::launch(self).await
+    }
+}
@@ -0,0 +1,271 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::CctlError;
+
+/// Changes to the chainspec a network is set up with, applied on top of the chainspec passed
+/// to the builder or otherwise cctl's default one.
+///
+/// Values are addressed by their dotted TOML key, e.g. `core.minimum_era_height`, and have to
+/// exist in the chainspec which is patched, so typos are reported instead of being ignored.
+///
+/// ```
+/// use std::time::Duration;
+/// use cctl::ChainspecOverrides;
+///
+/// let overrides = ChainspecOverrides::new()
+///     .with_era_duration(Duration::from_secs(10))
+///     .with_minimum_era_height(2)
+///     .with_value("core.auction_delay", 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChainspecOverrides {
+    values: Vec<(String, toml::Value)>,
+}
+
+impl ChainspecOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value at the dotted TOML key, later overrides of the same key take precedence
+    pub fn with_value(mut self, key: impl Into<String>, value: impl Into<toml::Value>) -> Self {
+        self.values.push((key.into(), value.into()));
+        self
+    }
+
+    /// `core.minimum_block_time`, which replaced the minimum round length in casper 2.0
+    pub fn with_minimum_block_time(self, minimum_block_time: Duration) -> Self {
+        self.with_value("core.minimum_block_time", time_diff(minimum_block_time))
+    }
+
+    /// `core.era_duration`
+    pub fn with_era_duration(self, era_duration: Duration) -> Self {
+        self.with_value("core.era_duration", time_diff(era_duration))
+    }
+
+    /// `core.minimum_era_height`
+    pub fn with_minimum_era_height(self, minimum_era_height: u32) -> Self {
+        self.with_value("core.minimum_era_height", minimum_era_height)
+    }
+
+    /// `core.validator_slots`
+    pub fn with_validator_slots(self, validator_slots: u32) -> Self {
+        self.with_value("core.validator_slots", validator_slots)
+    }
+
+    /// `transactions.block_gas_limit`, saturated at the largest integer TOML can represent
+    pub fn with_block_gas_limit(self, block_gas_limit: u64) -> Self {
+        let block_gas_limit = i64::try_from(block_gas_limit).unwrap_or(i64::MAX);
+        self.with_value("transactions.block_gas_limit", block_gas_limit)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub(crate) fn extend(&mut self, other: ChainspecOverrides) {
+        self.values.extend(other.values);
+    }
+
+    /// Applies the overrides to a parsed chainspec
+    pub(crate) fn apply(&self, chainspec: &mut toml::Table) -> Result<(), CctlError> {
+        apply_values(chainspec, &self.values, "chainspec")
+    }
+}
+
+/// Replaces the values at the dotted keys, which have to exist in `table` already.
+/// `document` names the patched file in errors.
+pub(crate) fn apply_values(
+    table: &mut toml::Table,
+    values: &[(String, toml::Value)],
+    document: &str,
+) -> Result<(), CctlError> {
+    for (key, value) in values {
+        let unknown_key = || {
+            CctlError::InvalidConfig(format!(
+                "can't override '{key}', the {document} has no such value"
+            ))
+        };
+        let mut segments = key.split('.');
+        let name = segments.next_back().ok_or_else(unknown_key)?;
+        let mut table = &mut *table;
+        for segment in segments {
+            table = table
+                .get_mut(segment)
+                .and_then(toml::Value::as_table_mut)
+                .ok_or_else(unknown_key)?;
+        }
+        *table.get_mut(name).ok_or_else(unknown_key)? = value.clone();
+    }
+    Ok(())
+}
+
+/// Formats a duration the way the chainspec's `TimeDiff` values are written
+fn time_diff(duration: Duration) -> String {
+    format!("{} ms", duration.as_millis())
+}
+
+/// The chainspec cctl generates the network's genesis from
+pub(crate) fn genesis_chainspec_path(assets_dir: &Path) -> PathBuf {
+    assets_dir.join("genesis/chainspec.toml")
+}
+
+/// The chainspec template `cctl-infra-net-setup` uses if none is passed, located in the
+/// casper-node checkout at `CCTL_CASPER_HOME`
+pub(crate) fn default_chainspec_path() -> Result<PathBuf, CctlError> {
+    casper_home_chainspec_path(env::var_os("CCTL_CASPER_HOME").map(PathBuf::from))
+}
+
+fn casper_home_chainspec_path(casper_home: Option<PathBuf>) -> Result<PathBuf, CctlError> {
+    let casper_home = casper_home.ok_or_else(|| {
+        CctlError::InvalidConfig(
+            "chainspec overrides need a chainspec path or CCTL_CASPER_HOME to locate cctl's default chainspec"
+                .to_string(),
+        )
+    })?;
+    let chainspec_path = casper_home.join("resources/local/chainspec.toml.in");
+    if !chainspec_path.is_file() {
+        return Err(CctlError::InvalidConfig(format!(
+            "cctl's default chainspec {} doesn't exist",
+            chainspec_path.display()
+        )));
+    }
+    Ok(chainspec_path)
+}
+
+/// Reads the `network.name` of a chainspec.toml
+pub(crate) fn read_chain_name(chainspec_path: &Path) -> Result<String, CctlError> {
+    let chainspec = read_toml(chainspec_path)?;
+    chainspec
+        .get("network")
+        .and_then(|network| network.get("name"))
+        .and_then(toml::Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| CctlError::InvalidToml {
+            path: chainspec_path.to_path_buf(),
+            message: "missing string value 'network.name'".to_string(),
+        })
+}
+
+/// Applies the overrides to the chainspec at `base_path` and writes the result to `path`
+pub(crate) fn write_chainspec(
+    base_path: &Path,
+    overrides: &ChainspecOverrides,
+    path: &Path,
+) -> Result<(), CctlError> {
+    let mut chainspec = read_toml(base_path)?;
+    overrides.apply(&mut chainspec)?;
+    write_toml(path, &chainspec)
+}
+
+pub(crate) fn read_toml(path: &Path) -> Result<toml::Table, CctlError> {
+    fs::read_to_string(path)?
+        .parse()
+        .map_err(|err: toml::de::Error| CctlError::InvalidToml {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })
+}
+
+pub(crate) fn write_toml(path: &Path, table: &toml::Table) -> Result<(), CctlError> {
+    let content = toml::to_string(table).map_err(|err| CctlError::InvalidToml {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })?;
+    Ok(fs::write(path, content)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_read_chain_name() {
+        let dir = tempdir().unwrap();
+        let chainspec_path = dir.path().join("chainspec.toml");
+        fs::write(
+            &chainspec_path,
+            "[protocol]\nversion = '2.0.0'\n\n[network]\nname = 'casper-custom'\nmaximum_net_message_size = 25_165_824\n",
+        )
+        .unwrap();
+
+        assert_eq!("casper-custom", read_chain_name(&chainspec_path).unwrap());
+    }
+
+    #[test]
+    fn test_read_chain_name_fails_without_network_name() {
+        let dir = tempdir().unwrap();
+        let chainspec_path = dir.path().join("chainspec.toml");
+        fs::write(&chainspec_path, "[protocol]\nversion = '2.0.0'\n").unwrap();
+
+        assert!(matches!(
+            read_chain_name(&chainspec_path),
+            Err(CctlError::InvalidToml { .. })
+        ));
+    }
+
+    #[test]
+    fn test_casper_home_chainspec_path() {
+        let casper_home = tempdir().unwrap();
+        let local_resources_dir = casper_home.path().join("resources/local");
+        fs::create_dir_all(&local_resources_dir).unwrap();
+
+        assert!(matches!(
+            casper_home_chainspec_path(None),
+            Err(CctlError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            casper_home_chainspec_path(Some(casper_home.path().to_path_buf())),
+            Err(CctlError::InvalidConfig(_))
+        ));
+        fs::write(local_resources_dir.join("chainspec.toml.in"), "").unwrap();
+        assert_eq!(
+            local_resources_dir.join("chainspec.toml.in"),
+            casper_home_chainspec_path(Some(casper_home.path().to_path_buf())).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_apply_chainspec_overrides() {
+        let mut chainspec: toml::Table =
+            "[core]\nera_duration = '41 seconds'\nminimum_era_height = 5\n\n[transactions]\nblock_gas_limit = 1\n"
+                .parse()
+                .unwrap();
+
+        ChainspecOverrides::new()
+            .with_era_duration(Duration::from_secs(10))
+            .with_minimum_era_height(2)
+            .with_value("core.minimum_era_height", 3)
+            .with_block_gas_limit(u64::MAX)
+            .apply(&mut chainspec)
+            .unwrap();
+
+        let expected: toml::Table =
+            "[core]\nera_duration = '10000 ms'\nminimum_era_height = 3\n\n[transactions]\nblock_gas_limit = 9223372036854775807\n"
+                .parse()
+                .unwrap();
+        assert_eq!(expected, chainspec);
+    }
+
+    #[test]
+    fn test_apply_chainspec_overrides_fails_for_unknown_keys() {
+        let mut chainspec: toml::Table = "[core]\nera_duration = '41 seconds'\n".parse().unwrap();
+
+        for key in [
+            "core.era_durations",
+            "highway.maximum_round_length",
+            "core.era_duration.ms",
+        ] {
+            assert!(matches!(
+                ChainspecOverrides::new()
+                    .with_value(key, 1)
+                    .apply(&mut chainspec),
+                Err(CctlError::InvalidConfig(_))
+            ));
+        }
+    }
+}
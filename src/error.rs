@@ -0,0 +1,130 @@
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use casper_types::DeployHash;
+
+use super::Topology;
+
+/// Errors that can occur while spinning up, interacting with or tearing down a CCTL network
+#[derive(Debug, thiserror::Error)]
+pub enum CctlError {
+    /// The cctl script is not on the `PATH`, usually because cctl is not installed
+    #[error("cctl script '{script}' not found, make sure cctl is installed and on the PATH")]
+    ScriptNotFound { script: String },
+
+    /// The cctl script exited unsuccessfully, `exit_code` is `None` if it was killed by a signal
+    #[error("cctl script '{script}' failed with exit code {exit_code:?}: {stderr}")]
+    ScriptFailed {
+        script: String,
+        exit_code: Option<i32>,
+        stderr: String,
+    },
+
+    /// The cctl script wrote output which isn't valid UTF-8
+    #[error("cctl script '{script}' produced output that is not valid UTF-8")]
+    NonUtf8Output { script: String },
+
+    /// The output of the cctl script doesn't have the expected format
+    #[error("failed to parse the output of cctl script '{script}': {input}")]
+    ParseError { script: String, input: String },
+
+    /// The network configuration is inconsistent
+    #[error("invalid network configuration: {0}")]
+    InvalidConfig(String),
+
+    /// cctl set up other nodes than the configured topology asks for, e.g. because the
+    /// installed cctl version doesn't support configuring the topology
+    #[error("cctl set up the topology {actual:?} instead of the requested {expected:?}")]
+    TopologyMismatch {
+        expected: Topology,
+        actual: Topology,
+    },
+
+    /// The cctl port listing doesn't contain an entry for a started node
+    #[error("can't find ports for node with id {node_id}")]
+    PortsMissing { node_id: u8 },
+
+    /// The cctl port listing doesn't contain an entry for a started sidecar
+    #[error("can't find ports for sidecar with id {node_id}")]
+    SidecarPortsMissing { node_id: u8 },
+
+    /// The network has no node with this id
+    #[error("the network has no node with id {node_id}")]
+    NodeNotFound { node_id: u8 },
+
+    /// The network has no sidecar with this node id
+    #[error("the network has no sidecar with id {node_id}")]
+    SidecarNotFound { node_id: u8 },
+
+    /// The network has no node in this validator group
+    #[error("the network has no validator group with id {validator_group_id}")]
+    ValidatorGroupNotFound { validator_group_id: u8 },
+
+    /// The node didn't answer requests or reach the expected reactor state and block height in time
+    #[error("node {node_id} didn't become ready within {timeout:?}: {last_error}")]
+    NodeNotReady {
+        node_id: u8,
+        timeout: Duration,
+        last_error: String,
+    },
+
+    /// The sidecar didn't answer RPC requests in time
+    #[error("sidecar {node_id} didn't become ready within {timeout:?}: {last_error}")]
+    SidecarNotReady {
+        node_id: u8,
+        timeout: Duration,
+        last_error: String,
+    },
+
+    /// A TOML file, e.g. the chainspec, couldn't be parsed or lacks an expected value
+    #[error("invalid TOML file '{path}': {message}")]
+    InvalidToml { path: PathBuf, message: String },
+
+    /// The network has no sidecar whose RPC could be used
+    #[error("the network has no sidecar to send requests to")]
+    NoSidecar,
+
+    /// None of the network's nodes is running
+    #[error("the network has no running node to send requests to")]
+    NoRunningNode,
+
+    /// There is no generated cctl user with this index
+    #[error("user-{index} doesn't exist in the cctl assets")]
+    UserNotFound { index: u8 },
+
+    /// A secret or public key couldn't be loaded
+    #[error("failed to load key '{path}': {message}")]
+    Key { path: PathBuf, message: String },
+
+    /// The deploy couldn't be built, e.g. because of an invalid chain name
+    #[error("failed to build deploy: {0}")]
+    DeployBuild(String),
+
+    /// The deploy was executed, but its execution failed
+    #[error("deploy {deploy_hash} failed: {message}")]
+    DeployFailed {
+        deploy_hash: DeployHash,
+        message: String,
+    },
+
+    /// An operation didn't complete in time
+    #[error("timed out after {elapsed:?} while {operation}")]
+    Timeout {
+        operation: String,
+        elapsed: Duration,
+    },
+
+    /// The node responded with something that was not expected
+    #[error("unexpected response: {0}")]
+    UnexpectedResponse(String),
+
+    #[error(transparent)]
+    Client(#[from] casper_client::Error),
+
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
@@ -0,0 +1,255 @@
+use std::time::{Duration, Instant};
+
+use backoff::{future::retry, ExponentialBackoff};
+use casper_client::{get_transaction, Error, JsonRpcId};
+use casper_types::{
+    execution::{execution_result_v1::ExecutionResultV1, Effects, ExecutionInfo, ExecutionResult},
+    BlockHash, Gas, TransactionHash, Transfer, TransferAddr, U512,
+};
+
+use super::{casper_client_verbosity, CctlError};
+
+/// The result of executing a deploy or transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionOutcome {
+    pub block_hash: BlockHash,
+    pub block_height: u64,
+    /// The amount charged for the execution
+    pub cost: U512,
+    /// `None` for results of the 1.x execution engine, which only reports the cost
+    pub consumed_gas: Option<Gas>,
+    /// `None` if the execution succeeded
+    pub error_message: Option<String>,
+    /// `None` for results of the 1.x execution engine
+    pub effects: Option<Effects>,
+    pub transfers: ExecutionTransfers,
+}
+
+/// The transfers made by an execution, in the form the execution engine reports them
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutionTransfers {
+    /// The 1.x execution engine only reports the addresses the transfers are stored under
+    V1(Vec<TransferAddr>),
+    V2(Vec<Transfer>),
+}
+
+impl ExecutionOutcome {
+    pub fn is_success(&self) -> bool {
+        self.error_message.is_none()
+    }
+
+    /// `None` if the transaction wasn't executed yet
+    fn from_execution_info(execution_info: ExecutionInfo) -> Option<Self> {
+        let ExecutionInfo {
+            block_hash,
+            block_height,
+            execution_result,
+        } = execution_info;
+        let outcome = match execution_result? {
+            ExecutionResult::V1(ExecutionResultV1::Success {
+                transfers, cost, ..
+            }) => Self {
+                block_hash,
+                block_height,
+                cost,
+                consumed_gas: None,
+                error_message: None,
+                effects: None,
+                transfers: ExecutionTransfers::V1(transfers),
+            },
+            ExecutionResult::V1(ExecutionResultV1::Failure {
+                transfers,
+                cost,
+                error_message,
+                ..
+            }) => Self {
+                block_hash,
+                block_height,
+                cost,
+                consumed_gas: None,
+                error_message: Some(error_message),
+                effects: None,
+                transfers: ExecutionTransfers::V1(transfers),
+            },
+            ExecutionResult::V2(execution_result_v2) => Self {
+                block_hash,
+                block_height,
+                cost: execution_result_v2.cost,
+                consumed_gas: Some(execution_result_v2.consumed),
+                error_message: execution_result_v2.error_message,
+                effects: Some(execution_result_v2.effects),
+                transfers: ExecutionTransfers::V2(execution_result_v2.transfers),
+            },
+        };
+        Some(outcome)
+    }
+}
+
+/// The longest pause between two polls for the outcome of a transaction
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Polls the sidecar at `rpc_url` until the deploy or transaction was executed, regardless of
+/// whether its execution succeeded
+pub(crate) async fn await_execution(
+    rpc_url: &str,
+    transaction_hash: TransactionHash,
+    timeout: Duration,
+) -> Result<ExecutionOutcome, CctlError> {
+    let start = Instant::now();
+    // The backoff doesn't give up on its own, the timeout also bounds a hanging request
+    let backoff = ExponentialBackoff {
+        max_interval: MAX_POLL_INTERVAL,
+        max_elapsed_time: None,
+        ..Default::default()
+    };
+    let poll = retry(backoff, || async {
+        let response = get_transaction(
+            JsonRpcId::Number(1),
+            rpc_url,
+            casper_client_verbosity(),
+            transaction_hash,
+            false,
+        )
+        .await
+        .map_err(|err| {
+            let elapsed = start.elapsed().as_secs();
+            tracing::info!("Waited {elapsed}s for transaction {transaction_hash} to be executed, the last reported error was: {err:?}");
+            err
+        })
+        .map_err(|err| match &err {
+            Error::ResponseIsHttpError { .. } | Error::FailedToGetResponse { .. } => {
+                backoff::Error::transient(CctlError::Client(err))
+            }
+            _ => backoff::Error::permanent(CctlError::Client(err)),
+        })?;
+
+        response
+            .result
+            .execution_info
+            .and_then(ExecutionOutcome::from_execution_info)
+            .ok_or_else(|| {
+                backoff::Error::transient(CctlError::UnexpectedResponse(format!(
+                    "transaction {transaction_hash} wasn't executed yet"
+                )))
+            })
+    });
+    tokio::time::timeout(timeout, poll)
+        .await
+        .map_err(|_| CctlError::Timeout {
+            operation: format!("waiting for transaction {transaction_hash} to be executed"),
+            elapsed: start.elapsed(),
+        })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use casper_types::{
+        account::AccountHash, execution::execution_result_v1::ExecutionEffect,
+        execution::ExecutionResultV2, InitiatorAddr,
+    };
+
+    fn execution_info(execution_result: ExecutionResult) -> ExecutionInfo {
+        ExecutionInfo {
+            block_hash: BlockHash::default(),
+            block_height: 7,
+            execution_result: Some(execution_result),
+        }
+    }
+
+    #[test]
+    fn test_outcome_of_a_pending_transaction() {
+        assert_eq!(
+            None,
+            ExecutionOutcome::from_execution_info(ExecutionInfo {
+                block_hash: BlockHash::default(),
+                block_height: 7,
+                execution_result: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_outcome_of_a_successful_v1_execution() {
+        let outcome = ExecutionOutcome::from_execution_info(execution_info(ExecutionResult::V1(
+            ExecutionResultV1::Success {
+                effect: ExecutionEffect::default(),
+                transfers: vec![TransferAddr::new([2; 32])],
+                cost: U512::from(100u64),
+            },
+        )))
+        .unwrap();
+
+        assert_eq!(
+            ExecutionOutcome {
+                block_hash: BlockHash::default(),
+                block_height: 7,
+                cost: U512::from(100u64),
+                consumed_gas: None,
+                error_message: None,
+                effects: None,
+                transfers: ExecutionTransfers::V1(vec![TransferAddr::new([2; 32])]),
+            },
+            outcome
+        );
+        assert!(outcome.is_success());
+    }
+
+    #[test]
+    fn test_outcome_of_a_failed_v1_execution() {
+        let outcome = ExecutionOutcome::from_execution_info(execution_info(ExecutionResult::V1(
+            ExecutionResultV1::Failure {
+                effect: ExecutionEffect::default(),
+                transfers: Vec::new(),
+                cost: U512::from(100u64),
+                error_message: "Out of gas error".to_string(),
+            },
+        )))
+        .unwrap();
+
+        assert_eq!(U512::from(100u64), outcome.cost);
+        assert_eq!(Some("Out of gas error".to_string()), outcome.error_message);
+        assert_eq!(ExecutionTransfers::V1(Vec::new()), outcome.transfers);
+        assert!(!outcome.is_success());
+    }
+
+    #[test]
+    fn test_outcome_of_a_v2_execution() {
+        let execution_result = |error_message: Option<String>| {
+            ExecutionResult::V2(ExecutionResultV2 {
+                initiator: InitiatorAddr::AccountHash(AccountHash::new([1; 32])),
+                error_message,
+                limit: Gas::new(300u64),
+                consumed: Gas::new(200u64),
+                cost: U512::from(300u64),
+                payment: Vec::new(),
+                transfers: Vec::new(),
+                size_estimate: 0,
+                effects: Effects::new(),
+            })
+        };
+
+        let outcome =
+            ExecutionOutcome::from_execution_info(execution_info(execution_result(None))).unwrap();
+        assert_eq!(
+            ExecutionOutcome {
+                block_hash: BlockHash::default(),
+                block_height: 7,
+                cost: U512::from(300u64),
+                consumed_gas: Some(Gas::new(200u64)),
+                error_message: None,
+                effects: Some(Effects::new()),
+                transfers: ExecutionTransfers::V2(Vec::new()),
+            },
+            outcome
+        );
+        assert!(outcome.is_success());
+
+        let outcome = ExecutionOutcome::from_execution_info(execution_info(execution_result(
+            Some("User error: 1".to_string()),
+        )))
+        .unwrap();
+        assert_eq!(Some("User error: 1".to_string()), outcome.error_message);
+        assert!(!outcome.is_success());
+    }
+}
@@ -0,0 +1,312 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use casper_types::{PublicKey, SecretKey, U512};
+
+use super::accounts::{self, AccountKeys};
+use super::chainspec::{read_toml, write_toml};
+use super::{node_config, CctlError};
+
+/// The directory in the working directory the keys of generated genesis accounts are put into
+const GENESIS_ACCOUNTS_DIR: &str = "genesis-accounts";
+
+/// An additional account funded at genesis, optionally bonded as validator or delegating
+/// to a validator.
+///
+/// ```
+/// use cctl::GenesisAccount;
+///
+/// let validator = GenesisAccount::generated(1_000_000_000_000_000u64.into())
+///     .with_bonded_amount(500_000_000_000u64.into(), 10);
+/// let delegator = GenesisAccount::generated(1_000_000_000_000_000u64.into())
+///     .with_delegation_to_node(1, 500_000_000_000u64.into());
+/// ```
+#[derive(Debug, Clone)]
+pub struct GenesisAccount {
+    /// `None` if a key pair is generated for the account
+    public_key: Option<PublicKey>,
+    balance: U512,
+    role: Role,
+}
+
+#[derive(Debug, Clone)]
+enum Role {
+    Account,
+    Validator {
+        bonded_amount: U512,
+        delegation_rate: u8,
+    },
+    /// Delegators are listed separately from the other genesis accounts
+    Delegator {
+        delegatee: Delegatee,
+        delegated_amount: U512,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum Delegatee {
+    PublicKey(PublicKey),
+    /// The validator of a cctl node, whose keys only exist once the network was set up
+    Node(u8),
+}
+
+impl GenesisAccount {
+    /// An account of an existing public key
+    pub fn new(public_key: PublicKey, balance: U512) -> Self {
+        Self {
+            public_key: Some(public_key),
+            balance,
+            role: Role::Account,
+        }
+    }
+
+    /// An account of a freshly generated ed25519 key pair, which is available through
+    /// [`CCTLNetwork::genesis_accounts`](crate::CCTLNetwork::genesis_accounts)
+    pub fn generated(balance: U512) -> Self {
+        Self {
+            public_key: None,
+            balance,
+            role: Role::Account,
+        }
+    }
+
+    /// Makes the account a genesis validator, replacing a delegation.
+    ///
+    /// No node runs with the account's key, so its bonded weight takes part in consensus
+    /// without ever signing. Keep it small compared to the cctl nodes' weight, since otherwise
+    /// the network can't finalize blocks and its startup times out.
+    pub fn with_bonded_amount(mut self, bonded_amount: U512, delegation_rate: u8) -> Self {
+        self.role = Role::Validator {
+            bonded_amount,
+            delegation_rate,
+        };
+        self
+    }
+
+    /// Delegates to the validator with the given public key at genesis, replacing a bond
+    pub fn with_delegation(mut self, validator: PublicKey, delegated_amount: U512) -> Self {
+        self.role = Role::Delegator {
+            delegatee: Delegatee::PublicKey(validator),
+            delegated_amount,
+        };
+        self
+    }
+
+    /// Delegates to the validator of the cctl node with the given id at genesis, replacing a bond
+    pub fn with_delegation_to_node(mut self, node_id: u8, delegated_amount: U512) -> Self {
+        self.role = Role::Delegator {
+            delegatee: Delegatee::Node(node_id),
+            delegated_amount,
+        };
+        self
+    }
+}
+
+/// Generates the keys of the generated genesis accounts and adds all genesis accounts to
+/// the accounts.toml files cctl set up, i.e. the genesis one and those of every node.
+/// Returns the key directories of the generated accounts, in order.
+pub(crate) fn add_genesis_accounts(
+    working_dir: &Path,
+    assets_dir: &Path,
+    genesis_accounts: &[GenesisAccount],
+) -> Result<Vec<PathBuf>, CctlError> {
+    // Keys left over from an earlier network in the same working directory
+    let genesis_accounts_dir = working_dir.join(GENESIS_ACCOUNTS_DIR);
+    if genesis_accounts_dir.exists() {
+        fs::remove_dir_all(&genesis_accounts_dir)?;
+    }
+    let mut generated = Vec::new();
+    let mut accounts = Vec::new();
+    let mut delegators = Vec::new();
+    for genesis_account in genesis_accounts {
+        let public_key = match &genesis_account.public_key {
+            Some(public_key) => public_key.clone(),
+            None => {
+                let keys_dir =
+                    genesis_accounts_dir.join(format!("account-{}", generated.len() + 1));
+                let public_key = generate_keys(&keys_dir)?;
+                generated.push(keys_dir);
+                public_key
+            }
+        };
+        match &genesis_account.role {
+            Role::Account => {
+                accounts.push(account_entry(&public_key, genesis_account.balance, None)?)
+            }
+            Role::Validator {
+                bonded_amount,
+                delegation_rate,
+            } => accounts.push(account_entry(
+                &public_key,
+                genesis_account.balance,
+                Some((*bonded_amount, *delegation_rate)),
+            )?),
+            Role::Delegator {
+                delegatee,
+                delegated_amount,
+            } => {
+                let validator = match delegatee {
+                    Delegatee::PublicKey(validator) => validator.clone(),
+                    Delegatee::Node(node_id) => {
+                        AccountKeys::load(&accounts::node_keys_dir(assets_dir, *node_id))?
+                            .public_key
+                    }
+                };
+                delegators.push(delegator_entry(
+                    &validator,
+                    &public_key,
+                    genesis_account.balance,
+                    *delegated_amount,
+                )?);
+            }
+        }
+    }
+
+    for accounts_path in accounts_paths(assets_dir)? {
+        tracing::info!("Adding genesis accounts to {}", accounts_path.display());
+        let mut accounts_toml = read_toml(&accounts_path)?;
+        append(&mut accounts_toml, "accounts", &accounts);
+        append(&mut accounts_toml, "delegators", &delegators);
+        write_toml(&accounts_path, &accounts_toml)?;
+    }
+    Ok(generated)
+}
+
+fn generate_keys(keys_dir: &Path) -> Result<PublicKey, CctlError> {
+    let key_error = |path: &Path, err: &dyn std::fmt::Display| CctlError::Key {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    };
+    fs::create_dir_all(keys_dir)?;
+    let secret_key_path = keys_dir.join("secret_key.pem");
+    let secret_key =
+        SecretKey::generate_ed25519().map_err(|err| key_error(&secret_key_path, &err))?;
+    secret_key
+        .to_file(&secret_key_path)
+        .map_err(|err| key_error(&secret_key_path, &err))?;
+    let public_key_path = keys_dir.join("public_key.pem");
+    let public_key = PublicKey::from(&secret_key);
+    public_key
+        .to_file(&public_key_path)
+        .map_err(|err| key_error(&public_key_path, &err))?;
+    Ok(public_key)
+}
+
+fn account_entry(
+    public_key: &PublicKey,
+    balance: U512,
+    validator: Option<(U512, u8)>,
+) -> Result<toml::Value, CctlError> {
+    let mut account = toml::Table::new();
+    account.insert("public_key".to_string(), public_key_value(public_key)?);
+    account.insert("balance".to_string(), balance.to_string().into());
+    if let Some((bonded_amount, delegation_rate)) = validator {
+        let mut validator = toml::Table::new();
+        validator.insert(
+            "bonded_amount".to_string(),
+            bonded_amount.to_string().into(),
+        );
+        validator.insert("delegation_rate".to_string(), delegation_rate.into());
+        account.insert("validator".to_string(), validator.into());
+    }
+    Ok(account.into())
+}
+
+fn delegator_entry(
+    validator: &PublicKey,
+    delegator: &PublicKey,
+    balance: U512,
+    delegated_amount: U512,
+) -> Result<toml::Value, CctlError> {
+    let mut delegator_entry = toml::Table::new();
+    delegator_entry.insert(
+        "validator_public_key".to_string(),
+        public_key_value(validator)?,
+    );
+    delegator_entry.insert(
+        "delegator_public_key".to_string(),
+        public_key_value(delegator)?,
+    );
+    delegator_entry.insert("balance".to_string(), balance.to_string().into());
+    delegator_entry.insert(
+        "delegated_amount".to_string(),
+        delegated_amount.to_string().into(),
+    );
+    Ok(delegator_entry.into())
+}
+
+/// Public keys are serialized as their hex representation in human readable formats
+fn public_key_value(public_key: &PublicKey) -> Result<toml::Value, CctlError> {
+    toml::Value::try_from(public_key)
+        .map_err(|err| CctlError::InvalidConfig(format!("can't serialize public key: {err}")))
+}
+
+/// Appends the entries to the array of tables at `key`, creating it if necessary
+fn append(table: &mut toml::Table, key: &str, entries: &[toml::Value]) {
+    if entries.is_empty() {
+        return;
+    }
+    match table.get_mut(key).and_then(toml::Value::as_array_mut) {
+        Some(array) => array.extend_from_slice(entries),
+        None => {
+            table.insert(key.to_string(), entries.to_vec().into());
+        }
+    }
+}
+
+/// The genesis accounts.toml and the copies cctl puts next to every node's config.toml
+fn accounts_paths(assets_dir: &Path) -> Result<Vec<PathBuf>, CctlError> {
+    let mut accounts_paths = vec![assets_dir.join("genesis/accounts.toml")];
+    accounts_paths.extend(
+        node_config::node_config_dirs(assets_dir)?
+            .into_iter()
+            .map(|config_dir| config_dir.join("accounts.toml")),
+    );
+    Ok(accounts_paths
+        .into_iter()
+        .filter(|accounts_path| accounts_path.is_file())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_genesis_accounts() {
+        let mut accounts_toml: toml::Table =
+            "[[accounts]]\npublic_key = '01aa'\nbalance = '1000'\n"
+                .parse()
+                .unwrap();
+        let account: toml::Value = "public_key = '01bb'\nbalance = '2000'\n"
+            .parse::<toml::Table>()
+            .unwrap()
+            .into();
+        let delegator: toml::Value = "validator_public_key = '01aa'\ndelegator_public_key = '01bb'\nbalance = '2000'\ndelegated_amount = '10'\n"
+            .parse::<toml::Table>()
+            .unwrap()
+            .into();
+
+        append(&mut accounts_toml, "accounts", &[account]);
+        append(&mut accounts_toml, "delegators", &[delegator]);
+
+        let expected: toml::Table = r#"
+            [[accounts]]
+            public_key = '01aa'
+            balance = '1000'
+
+            [[accounts]]
+            public_key = '01bb'
+            balance = '2000'
+
+            [[delegators]]
+            validator_public_key = '01aa'
+            delegator_public_key = '01bb'
+            balance = '2000'
+            delegated_amount = '10'
+        "#
+        .parse()
+        .unwrap();
+        assert_eq!(expected, accounts_toml);
+    }
+}
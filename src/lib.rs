@@ -1,38 +1,78 @@
+mod accounts;
+pub mod builder;
+mod chainspec;
+mod error;
+mod execution;
+mod genesis;
+mod node_config;
 pub mod parsers;
+mod profile;
+mod rest;
+mod script;
+mod sse;
+mod topology;
 
-use anyhow::anyhow;
-use backoff::{future::retry, ExponentialBackoff};
+use futures_util::Stream;
 use hex::FromHex;
-use itertools::{Either, Itertools};
 use serde::{Deserialize, Serialize};
-use std::env;
-use std::io::{self, Write};
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Arc;
 use std::{
     fs,
+    future::Future,
     time::{Duration, Instant},
 };
-use tempfile::tempdir;
+use tempfile::{tempdir, TempDir};
 
-use casper_client::{
-    get_deploy, get_state_root_hash, put_deploy, query_global_state, Error, JsonRpcId, Verbosity,
-};
+use casper_client::{get_state_root_hash, put_deploy, query_global_state, JsonRpcId, Verbosity};
 use casper_types::{
-    account::AccountHash,
-    contracts::ContractHash,
-    execution::{execution_result_v1::ExecutionResultV1, ExecutionResult},
-    runtime_args, DeployBuilder, ExecutableDeployItem, Key, PublicKey, RuntimeArgs, SecretKey,
-    StoredValue, TimeDiff, Timestamp,
+    account::AccountHash, contracts::ContractHash, runtime_args, DeployBuilder,
+    ExecutableDeployItem, Key, PublicKey, RuntimeArgs, SecretKey, StoredValue, TimeDiff, Timestamp,
+    TransactionHash,
 };
 
+pub use accounts::{AccountKeys, CctlUser};
+pub use builder::CCTLNetworkBuilder;
+pub use chainspec::ChainspecOverrides;
+pub use error::CctlError;
+use execution::await_execution;
+pub use execution::{ExecutionOutcome, ExecutionTransfers};
+pub use genesis::GenesisAccount;
+pub use node_config::{LogFormat, NodeConfigOverrides};
 use parsers::RawNodeType;
+pub use profile::NetworkProfile;
+pub use rest::{LastAddedBlockInfo, NodeStatus, ReactorState};
+use script::{block_on, run_script};
+pub use script::{CctlCommandRunner, ReplayCommandRunner, ScriptOutput, ShellCommandRunner};
+pub use sse::{EventData, SseEvent};
+pub use topology::Topology;
 
+/// The state of a node or sidecar process, as reported by supervisord
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum NodeState {
     Running,
     Stopped,
+    Starting,
+    /// The process exited while starting and will be restarted
+    Backoff,
+    Stopping,
+    /// The process exited on its own while running
+    Exited,
+    /// The process couldn't be started
+    Fatal,
+    Unknown,
+}
+
+/// Supervisord's details about a node or sidecar process
+#[derive(Debug, PartialEq, Clone)]
+pub enum ProcessInfo {
+    Running {
+        pid: u32,
+        uptime: Duration,
+    },
+    /// Describes why the process isn't running, e.g. `Not started` or an exit reason
+    Description(String),
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -46,9 +86,17 @@ pub struct CasperSidecar {
     pub id: u8,
     pub validator_group_id: u8,
     pub state: NodeState,
+    pub process: ProcessInfo,
     pub port: CasperSidecarPorts,
 }
 
+impl CasperSidecar {
+    /// The URL of the sidecar's JSON-RPC API
+    pub fn rpc_url(&self) -> String {
+        format!("http://0.0.0.0:{}/rpc", self.port.rpc_port)
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct CasperNodePorts {
     pub protocol_port: u16,
@@ -61,13 +109,64 @@ pub struct CasperNode {
     pub id: u8,
     pub validator_group_id: u8,
     pub state: NodeState,
+    pub process: ProcessInfo,
     pub port: CasperNodePorts,
+    assets_dir: PathBuf,
+}
+
+impl CasperNode {
+    /// Loads the node's validator keys
+    pub fn keys(&self) -> Result<AccountKeys, CctlError> {
+        AccountKeys::load(&accounts::node_keys_dir(&self.assets_dir, self.id))
+    }
+
+    /// The base URL of the node's REST API
+    pub fn rest_url(&self) -> String {
+        format!("http://0.0.0.0:{}", self.port.rest_port)
+    }
+
+    /// Fetches the node's status from its REST API
+    pub async fn status(&self) -> Result<NodeStatus, CctlError> {
+        rest::get_node_status(&self.rest_url()).await
+    }
+
+    /// The URL of the node's SSE event stream
+    pub fn sse_url(&self) -> String {
+        format!("http://0.0.0.0:{}/events", self.port.sse_port)
+    }
+
+    /// Subscribes to the node's events, starting with those emitted after subscribing.
+    /// The stream reconnects if the connection drops, resuming after the last received event.
+    pub fn events(&self) -> impl Stream<Item = Result<SseEvent, CctlError>> + Send {
+        sse::subscribe(self.sse_url(), None)
+    }
+
+    /// Subscribes to the node's events, replaying those the node still buffers from the
+    /// event with id `start_from` on
+    pub fn events_from(
+        &self,
+        start_from: u32,
+    ) -> impl Stream<Item = Result<SseEvent, CctlError>> + Send {
+        sse::subscribe(self.sse_url(), Some(start_from))
+    }
 }
 
 pub struct CCTLNetwork {
     pub working_dir: PathBuf,
     pub casper_nodes: Vec<CasperNode>,
     pub casper_sidecars: Vec<CasperSidecar>,
+    script_timeout: Duration,
+    runner: Arc<dyn CctlCommandRunner>,
+    /// Additional environment variables passed to every cctl script, e.g. the topology
+    script_env: Vec<(String, String)>,
+    /// Set once the network was stopped, so dropping it doesn't stop it again
+    stopped: bool,
+    /// The temporary working directory, which is removed once the network was stopped
+    temp_dir: Option<TempDir>,
+    keep_working_dir: bool,
+    chain_name: String,
+    /// The key directories of the generated genesis accounts
+    genesis_account_dirs: Vec<PathBuf>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -76,6 +175,45 @@ pub struct DeployableContract {
     pub hash_name: String,
     pub runtime_args: Option<RuntimeArgs>,
     pub path: PathBuf,
+    /// The account that signs the deploy, the network's default deployer is used if not set
+    #[serde(default)]
+    pub deployer: Option<Deployer>,
+}
+
+/// The account that signs contract deploys
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Deployer {
+    /// One of the generated cctl users located at `assets/users/user-<index>`
+    User(u8),
+    /// A secret key PEM file, the public key is derived from it
+    SecretKey(PathBuf),
+}
+
+impl Default for Deployer {
+    fn default() -> Self {
+        Deployer::User(1)
+    }
+}
+
+impl Deployer {
+    fn load_keys(&self, assets_dir: &Path) -> Result<(SecretKey, PublicKey), CctlError> {
+        match self {
+            Deployer::User(index) => {
+                let AccountKeys {
+                    secret_key,
+                    public_key,
+                    ..
+                } = CctlUser::load(assets_dir, *index)?.keys;
+                Ok((secret_key, public_key))
+            }
+            Deployer::SecretKey(path) => {
+                let secret_key = accounts::load_secret_key(path)?;
+                let public_key = PublicKey::from(&secret_key);
+                Ok((secret_key, public_key))
+            }
+        }
+    }
 }
 
 impl FromStr for DeployableContract {
@@ -100,10 +238,78 @@ pub fn casper_client_verbosity() -> Verbosity {
 // max amount allowed to be used on gas fees
 pub const MAX_GAS_FEE_PAYMENT_AMOUNT: u64 = 10_000_000_000_000;
 
+/// The default time to wait for a deployed contract to be executed
+pub const DEFAULT_DEPLOY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The default time a single cctl script may run before it is killed, and the network has to
+/// produce block 1 once started
+pub const DEFAULT_SCRIPT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The default time the running nodes and sidecars have to answer requests after block 1
+pub const DEFAULT_READINESS_TIMEOUT: Duration = Duration::from_secs(60);
+const READINESS_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The time a started or stopped node or sidecar has to reach its new state
+const PROCESS_STATE_TIMEOUT: Duration = Duration::from_secs(60);
+const PROCESS_STATE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+const CHAIN_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A node or sidecar process of the network, identified by its node id
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Process {
+    Node(u8),
+    Sidecar(u8),
+}
+
+impl Process {
+    /// The cctl script to apply `action`, i.e. start, stop or restart, to this process
+    fn script(self, action: &str) -> String {
+        match self {
+            Process::Node(_) => format!("cctl-infra-node-{action}"),
+            Process::Sidecar(_) => format!("cctl-infra-sidecar-{action}"),
+        }
+    }
+
+    fn node_id(self) -> u8 {
+        match self {
+            Process::Node(node_id) | Process::Sidecar(node_id) => node_id,
+        }
+    }
+}
+
+impl std::fmt::Display for Process {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Process::Node(node_id) => write!(f, "node {node_id}"),
+            Process::Sidecar(node_id) => write!(f, "sidecar {node_id}"),
+        }
+    }
+}
+
+impl From<RawNodeType> for (Process, NodeState, ProcessInfo) {
+    fn from(node_type: RawNodeType) -> Self {
+        match node_type {
+            RawNodeType::CasperNode(_, node_id, state, info) => {
+                (Process::Node(node_id), state, info)
+            }
+            RawNodeType::CasperSidecar(_, node_id, state, info) => {
+                (Process::Sidecar(node_id), state, info)
+            }
+        }
+    }
+}
+
 impl CCTLNetwork {
+    /// Returns a builder to configure and spin up a CCTL network
+    pub fn builder() -> CCTLNetworkBuilder {
+        CCTLNetworkBuilder::new()
+    }
+
     /// Spins up a CCTL network, and deploys a contract if provided
     ///
-    /// If a chain spec and config path are not provided, the environment variables `CCTL_CHAINSPEC` and `CCTL_CONFIG` are used.
+    /// If a chain spec and config path are not provided, the environment variables `CCTL_CASPER_CHAINSPEC` and `CCTL_CASPER_NODE_CONFIG` are used.
+    /// This is a thin wrapper around [`CCTLNetworkBuilder`], which should be preferred for new code.
     ///
     /// WARNING: do not use this function in unit tests, only sequentially executed integration tests.
     /// Ensure that two instances of this function are not running at the same time even in different processes.
@@ -112,207 +318,900 @@ impl CCTLNetwork {
         contracts_to_deploy: Option<Vec<DeployableContract>>,
         chainspec_path: Option<PathBuf>,
         config_path: Option<PathBuf>,
-    ) -> anyhow::Result<CCTLNetwork> {
-        let chainspec_path: Option<PathBuf> =
-            chainspec_path.or_else(|| env::var("CCTL_CASPER_CHAINSPEC").ok().map(PathBuf::from));
-        let config_path =
-            config_path.or_else(|| env::var("CCTL_CASPER_NODE_CONFIG").ok().map(PathBuf::from));
-
-        let working_dir = working_dir
-            .map(|dir| {
-                std::fs::create_dir_all(&dir)
-                    .expect("Failed to create the provided working directory");
-                dir
-            })
-            .unwrap_or(tempdir()?.into_path());
+    ) -> Result<CCTLNetwork, CctlError> {
+        let mut builder =
+            CCTLNetwork::builder().with_contracts(contracts_to_deploy.unwrap_or_default());
+        if let Some(working_dir) = working_dir {
+            builder = builder.with_working_dir(working_dir);
+        }
+        if let Some(chainspec_path) = chainspec_path {
+            builder = builder.with_chainspec_path(chainspec_path);
+        }
+        if let Some(config_path) = config_path {
+            builder = builder.with_node_config_path(config_path);
+        }
+        builder.run().await
+    }
+
+    /// Spins up a CCTL network as configured by the builder
+    async fn launch(
+        CCTLNetworkBuilder {
+            working_dir,
+            contracts,
+            chainspec_path,
+            chainspec_overrides,
+            node_config_path,
+            node_config_overrides,
+            node_specific_config_overrides,
+            chain_name,
+            genesis_accounts,
+            topology,
+            profile,
+            deployer,
+            deploy_timeout,
+            script_timeout,
+            readiness_probe,
+            await_validating,
+            readiness_timeout,
+            runner,
+            keep_working_dir,
+        }: CCTLNetworkBuilder,
+    ) -> Result<CCTLNetwork, CctlError> {
+        let topology = topology.or(profile.topology());
+        let chainspec_overrides = {
+            let mut profile_overrides = profile.chainspec_overrides();
+            profile_overrides.extend(chainspec_overrides);
+            profile_overrides
+        };
+        let script_env = match topology {
+            Some(topology) => {
+                topology.validate()?;
+                topology.script_env()
+            }
+            None => Vec::new(),
+        };
+        let (working_dir, temp_dir) = match working_dir {
+            Some(dir) => {
+                fs::create_dir_all(&dir)?;
+                (dir, None)
+            }
+            None => {
+                let temp_dir = tempdir()?;
+                (temp_dir.path().to_path_buf(), Some(temp_dir))
+            }
+        };
         let assets_dir = working_dir.join("assets");
         tracing::info!("Working directory: {:?}", working_dir);
 
-        let mut setup_command = Command::new("cctl-infra-net-setup");
-        setup_command.env("CCTL_ASSETS", &assets_dir);
+        // Until the network guard below exists, a failure has to keep the temporary working
+        // directory itself
+        let setup = async {
+            let chainspec_path = if chainspec_overrides.is_empty() {
+                chainspec_path
+            } else {
+                let base_chainspec_path = match chainspec_path {
+                    Some(chainspec_path) => chainspec_path,
+                    None => chainspec::default_chainspec_path()?,
+                };
+                let chainspec_path = working_dir.join("chainspec.toml");
+                chainspec::write_chainspec(
+                    &base_chainspec_path,
+                    &chainspec_overrides,
+                    &chainspec_path,
+                )?;
+                Some(chainspec_path)
+            };
 
-        if let Some(chainspec_path) = chainspec_path {
-            setup_command.arg(format!("chainspec={}", chainspec_path.to_str().unwrap()));
+            let mut setup_args = Vec::new();
+            if let Some(chainspec_path) = chainspec_path {
+                setup_args.push(format!("chainspec={}", chainspec_path.display()));
+            };
+            if let Some(config_path) = node_config_path {
+                setup_args.push(format!("config={}", config_path.display()));
+            };
+
+            tracing::info!("Setting up network configuration");
+            run_script(
+                runner.as_ref(),
+                &assets_dir,
+                &script_env,
+                "cctl-infra-net-setup",
+                &setup_args,
+                script_timeout,
+            )
+            .await?;
+
+            if !node_config_overrides.is_empty() || !node_specific_config_overrides.is_empty() {
+                node_config::apply_node_config_overrides(
+                    &assets_dir,
+                    &node_config_overrides,
+                    &node_specific_config_overrides,
+                )?;
+            }
+
+            let genesis_account_dirs = if genesis_accounts.is_empty() {
+                Vec::new()
+            } else {
+                genesis::add_genesis_accounts(&working_dir, &assets_dir, &genesis_accounts)?
+            };
+
+            let chain_name = match chain_name {
+                Some(chain_name) => chain_name,
+                None => {
+                    chainspec::read_chain_name(&chainspec::genesis_chainspec_path(&assets_dir))?
+                }
+            };
+            tracing::info!("Chain name: {chain_name}");
+            Ok::<_, CctlError>((chain_name, genesis_account_dirs))
+        }
+        .await;
+        let (chain_name, genesis_account_dirs) = match setup {
+            Ok(setup) => setup,
+            Err(err) => {
+                if let Some(temp_dir) = temp_dir {
+                    let working_dir = temp_dir.into_path();
+                    tracing::info!("Keeping working directory: {:?}", working_dir);
+                }
+                return Err(err);
+            }
         };
 
-        if let Some(config_path) = config_path {
-            setup_command.arg(format!("config={}", config_path.to_str().unwrap()));
+        // From here on the network is stopped once this value is dropped, which covers
+        // every early return and panic below until it is handed over to the caller
+        let mut network = CCTLNetwork {
+            working_dir: working_dir.clone(),
+            casper_nodes: Vec::new(),
+            casper_sidecars: Vec::new(),
+            script_timeout,
+            runner: runner.clone(),
+            script_env: script_env.clone(),
+            stopped: false,
+            temp_dir,
+            // Keep the working directory for inspection in case the startup fails
+            keep_working_dir: true,
+            chain_name,
+            genesis_account_dirs,
         };
 
-        tracing::info!("Setting up network configuration");
-        let output = setup_command
-            .output()
-            .expect("Failed to setup network configuration");
-        let output = std::str::from_utf8(output.stdout.as_slice()).unwrap();
-        tracing::info!("{}", output);
-
-        let output = Command::new("cctl-infra-net-start")
-            .env("CCTL_ASSETS", &assets_dir)
-            .output()
-            .expect("Failed to start network");
-        let output = std::str::from_utf8(output.stdout.as_slice()).unwrap();
-        tracing::info!("{}", output);
-        let (_, nodes) = parsers::parse_cctl_infra_net_start_lines(output).unwrap();
+        let output = run_script(
+            runner.as_ref(),
+            &assets_dir,
+            &script_env,
+            "cctl-infra-net-start",
+            &[],
+            script_timeout,
+        )
+        .await?
+        .stdout;
+        let (_, nodes) = parsers::parse_cctl_infra_net_start_lines(&output)
+            .map_err(|_| parse_error("cctl-infra-net-start", &output))?;
 
         tracing::info!("Fetching the networks node ports");
-        let output = Command::new("cctl-infra-node-view-ports")
-            .env("CCTL_ASSETS", &assets_dir)
-            .output()
-            .expect("Failed to get the networks node ports");
-        let output = std::str::from_utf8(output.stdout.as_slice()).unwrap();
-        tracing::info!("{}", output);
-        let (_, node_ports) = parsers::parse_cctl_infra_node_view_port_lines(output).unwrap();
+        let output = run_script(
+            runner.as_ref(),
+            &assets_dir,
+            &script_env,
+            "cctl-infra-node-view-ports",
+            &[],
+            script_timeout,
+        )
+        .await?
+        .stdout;
+        let (_, node_ports) = parsers::parse_cctl_infra_node_view_port_lines(&output)
+            .map_err(|_| parse_error("cctl-infra-node-view-ports", &output))?;
 
         tracing::info!("Fetching the networks sidecar ports");
-        let output = Command::new("cctl-infra-sidecar-view-ports")
-            .env("CCTL_ASSETS", &assets_dir)
-            .output()
-            .expect("Failed to get the networks node ports");
-        let output = std::str::from_utf8(output.stdout.as_slice()).unwrap();
-        tracing::info!("{}", output);
-        let (_, sidecar_ports) = parsers::parse_cctl_infra_sidecar_view_port_lines(output).unwrap();
+        let output = run_script(
+            runner.as_ref(),
+            &assets_dir,
+            &script_env,
+            "cctl-infra-sidecar-view-ports",
+            &[],
+            script_timeout,
+        )
+        .await?
+        .stdout;
+        let (_, sidecar_ports) = parsers::parse_cctl_infra_sidecar_view_port_lines(&output)
+            .map_err(|_| parse_error("cctl-infra-sidecar-view-ports", &output))?;
 
         // Match the started nodes and sidecars with their respective ports
 
-        let (casper_nodes, casper_sidecars): (Vec<CasperNode>, Vec<CasperSidecar>) =
-            nodes.iter().partition_map(|node_type| match node_type {
-                RawNodeType::CasperNode(validator_group_id, node_id, state) => {
-                    if let Some(&(_, port)) = node_ports
+        for node_type in nodes {
+            match node_type {
+                RawNodeType::CasperNode(validator_group_id, node_id, state, process) => {
+                    let &(_, port) = node_ports
                         .iter()
-                        .find(|(node_id_ports, _)| node_id_ports == node_id)
-                    {
-                        Either::Left(CasperNode {
-                            validator_group_id: *validator_group_id,
-                            state: *state,
-                            id: *node_id,
-                            port,
-                        })
-                    } else {
-                        panic!("Can't find ports for node with id {}", node_id)
-                    }
+                        .find(|(node_id_ports, _)| *node_id_ports == node_id)
+                        .ok_or(CctlError::PortsMissing { node_id })?;
+                    network.casper_nodes.push(CasperNode {
+                        validator_group_id,
+                        state,
+                        process,
+                        id: node_id,
+                        port,
+                        assets_dir: assets_dir.clone(),
+                    });
                 }
-                RawNodeType::CasperSidecar(validator_group_id, node_id, state) => {
-                    if let Some(&(_, port)) = sidecar_ports
+                RawNodeType::CasperSidecar(validator_group_id, node_id, state, process) => {
+                    let &(_, port) = sidecar_ports
                         .iter()
-                        .find(|(node_id_ports, _)| node_id_ports == node_id)
-                    {
-                        Either::Right(CasperSidecar {
-                            validator_group_id: *validator_group_id,
-                            state: *state,
-                            id: *node_id,
-                            port: CasperSidecarPorts {
-                                node_client_port: port.node_client_port,
-                                rpc_port: port.rpc_port,
-                                speculative_exec_port: port.speculative_exec_port,
-                            },
-                        })
-                    } else {
-                        panic!("Can't find ports for sidecar with id {}", node_id)
-                    }
+                        .find(|(node_id_ports, _)| *node_id_ports == node_id)
+                        .ok_or(CctlError::SidecarPortsMissing { node_id })?;
+                    network.casper_sidecars.push(CasperSidecar {
+                        validator_group_id,
+                        state,
+                        process,
+                        id: node_id,
+                        port,
+                    });
                 }
-            });
-
-        tracing::info!("Waiting for block 1");
-        let output = Command::new("cctl-chain-await-until-block-n")
-            .env("CCTL_ASSETS", &assets_dir)
-            .arg("height=1")
-            .output()
-            .expect("Waiting for network to start processing blocks failed");
-        let output = std::str::from_utf8(output.stdout.as_slice()).unwrap();
-        tracing::info!("{}", output);
-
-        // FIXME: remove once sidecar <-> node communication is more reliable
-        std::thread::sleep(std::time::Duration::from_secs(2));
-
-        if let Some(contracts_to_deploy) = contracts_to_deploy {
-            let rpc_port = casper_sidecars.first().unwrap().port.rpc_port;
-            let casper_sidecar_rpc_url = format!("http://0.0.0.0:{rpc_port}/rpc");
-            let deployer_skey =
-                SecretKey::from_file(working_dir.join("assets/users/user-1/secret_key.pem"))?;
-            let deployer_pkey =
-                PublicKey::from_file(working_dir.join("assets/users/user-1/public_key.pem"))?;
+            }
+        }
+
+        if let Some(expected) = topology {
+            let actual = Topology::from_validator_groups(
+                network
+                    .casper_nodes
+                    .iter()
+                    .map(|node| node.validator_group_id),
+            );
+            if actual != expected {
+                return Err(CctlError::TopologyMismatch { expected, actual });
+            }
+        }
 
+        if readiness_probe {
+            tracing::info!("Waiting for block 1");
+            network.await_block_height(1, script_timeout).await?;
+            network.await_ready(readiness_timeout).await?;
+            if await_validating {
+                tracing::info!("Waiting for the genesis validators to validate");
+                network
+                    .await_validating(readiness_timeout, |node| node.validator_group_id <= 2)
+                    .await?;
+            }
+        }
+
+        if !contracts.is_empty() {
+            let casper_sidecar_rpc_url = network
+                .casper_sidecars
+                .first()
+                .ok_or(CctlError::NoSidecar)?
+                .rpc_url();
             let contracts_dir = working_dir.join("contracts");
             fs::create_dir_all(&contracts_dir)?;
 
-            for contract_to_deploy in contracts_to_deploy {
+            for contract_to_deploy in contracts {
+                let (deployer_skey, deployer_pkey) = contract_to_deploy
+                    .deployer
+                    .as_ref()
+                    .unwrap_or(&deployer)
+                    .load_keys(&assets_dir)?;
                 let (hash_name, contract_hash) = deploy_contract(
                     &casper_sidecar_rpc_url,
+                    &network.chain_name,
                     &deployer_skey,
                     &deployer_pkey.to_account_hash(),
                     &contract_to_deploy,
+                    deploy_timeout,
                 )
                 .await?;
-                fs::write(
-                    contracts_dir.join(hash_name),
-                    // For a ContractHash contract- will always be the prefix
-                    contract_hash
-                        .to_formatted_string()
-                        .strip_prefix("contract-")
-                        .unwrap(),
-                )?
+                let formatted_contract_hash = contract_hash.to_formatted_string();
+                let contract_hash_hex = formatted_contract_hash
+                    .strip_prefix("contract-")
+                    .ok_or_else(|| {
+                        CctlError::UnexpectedResponse(format!(
+                            "Expected a contract hash prefixed with 'contract-': {formatted_contract_hash}"
+                        ))
+                    })?;
+                fs::write(contracts_dir.join(hash_name), contract_hash_hex)?
             }
         }
-        Ok(CCTLNetwork {
-            working_dir,
-            casper_nodes,
-            casper_sidecars,
+        network.keep_working_dir = keep_working_dir;
+        Ok(network)
+    }
+
+    /// Waits until every running node answers status requests on its REST API and every running
+    /// sidecar answers RPC requests, which requires its connection to the node's binary port to
+    /// be working
+    async fn await_ready(&self, timeout: Duration) -> Result<(), CctlError> {
+        tracing::info!("Waiting for the nodes and sidecars to become ready");
+        let deadline = Instant::now() + timeout;
+        for node in &self.casper_nodes {
+            if node.state != NodeState::Running {
+                continue;
+            }
+            poll_until(deadline, || async {
+                node.status()
+                    .await
+                    .map(|_| ())
+                    .map_err(|err| err.to_string())
+            })
+            .await
+            .map_err(|last_error| CctlError::NodeNotReady {
+                node_id: node.id,
+                timeout,
+                last_error,
+            })?;
+        }
+        for sidecar in &self.casper_sidecars {
+            if sidecar.state != NodeState::Running {
+                continue;
+            }
+            let rpc_url = sidecar.rpc_url();
+            poll_until(deadline, || async {
+                get_state_root_hash(
+                    JsonRpcId::Number(1),
+                    &rpc_url,
+                    casper_client_verbosity(),
+                    None,
+                )
+                .await
+                .map_err(|err| err.to_string())?
+                .result
+                .state_root_hash
+                .map(|_| ())
+                .ok_or_else(|| "no state root hash in response".to_string())
+            })
+            .await
+            .map_err(|last_error| CctlError::SidecarNotReady {
+                node_id: sidecar.id,
+                timeout,
+                last_error,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Waits until the reactor of every running node reached the `Validate` state, i.e. the
+    /// node participates in consensus. Only validators reach it, so this is meant for nodes
+    /// which were bonded at genesis or whose bid already became active.
+    pub async fn await_all_validating(&self, timeout: Duration) -> Result<(), CctlError> {
+        tracing::info!("Waiting for the nodes to validate");
+        self.await_validating(timeout, |_| true).await
+    }
+
+    /// Waits until the reactor of every running node matching `filter` reached the `Validate` state
+    async fn await_validating(
+        &self,
+        timeout: Duration,
+        filter: impl Fn(&CasperNode) -> bool,
+    ) -> Result<(), CctlError> {
+        let deadline = Instant::now() + timeout;
+        for node in &self.casper_nodes {
+            if node.state != NodeState::Running || !filter(node) {
+                continue;
+            }
+            poll_until(deadline, || async {
+                match node.status().await {
+                    Ok(status) if status.reactor_state == ReactorState::Validate => Ok(()),
+                    Ok(status) => Err(format!("reactor state is {:?}", status.reactor_state)),
+                    Err(err) => Err(err.to_string()),
+                }
+            })
+            .await
+            .map_err(|last_error| CctlError::NodeNotReady {
+                node_id: node.id,
+                timeout,
+                last_error,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Waits until the chain reached the given block height, returning the block reached.
+    ///
+    /// Like the other `await_*` methods, this polls the REST `/status` endpoint of the first
+    /// running node rather than following its event stream: a poll holds no connection state
+    /// to resume, and tolerates the node restarting in between. Use [`CasperNode::events`]
+    /// to observe every block as it is added.
+    pub async fn await_block_height(
+        &self,
+        height: u64,
+        timeout: Duration,
+    ) -> Result<LastAddedBlockInfo, CctlError> {
+        self.await_block(format!("waiting for block {height}"), timeout, |block| {
+            block.height >= height
+        })
+        .await
+    }
+
+    /// Waits until `count` blocks were added on top of the current one
+    pub async fn await_blocks(
+        &self,
+        count: u64,
+        timeout: Duration,
+    ) -> Result<LastAddedBlockInfo, CctlError> {
+        let mut height = None;
+        self.await_block(format!("waiting for {count} blocks"), timeout, |block| {
+            block.height >= *height.get_or_insert(block.height + count)
+        })
+        .await
+    }
+
+    /// Waits until the chain reached the given era, returning its first block seen
+    pub async fn await_era(
+        &self,
+        era_id: u64,
+        timeout: Duration,
+    ) -> Result<LastAddedBlockInfo, CctlError> {
+        self.await_block(format!("waiting for era {era_id}"), timeout, |block| {
+            block.era_id >= era_id
         })
+        .await
+    }
+
+    /// Waits until the era following the current one started
+    pub async fn await_next_era(&self, timeout: Duration) -> Result<LastAddedBlockInfo, CctlError> {
+        let mut era_id = None;
+        self.await_block("waiting for the next era".to_string(), timeout, |block| {
+            block.era_id >= *era_id.get_or_insert(block.era_id + 1)
+        })
+        .await
+    }
+
+    /// Waits until the deploy or transaction with the given hash was executed, through the RPC
+    /// of the first running sidecar. A failed execution is reported by the outcome's
+    /// `error_message` rather than as an error.
+    pub async fn wait_for_execution(
+        &self,
+        hash: impl Into<TransactionHash>,
+        timeout: Duration,
+    ) -> Result<ExecutionOutcome, CctlError> {
+        let rpc_url = self
+            .casper_sidecars
+            .iter()
+            .find(|sidecar| sidecar.state == NodeState::Running)
+            .ok_or(CctlError::NoSidecar)?
+            .rpc_url();
+        await_execution(&rpc_url, hash.into(), timeout).await
+    }
+
+    /// The latest block added by the first running node, as reported by its REST API
+    async fn last_added_block(&self) -> Result<LastAddedBlockInfo, CctlError> {
+        self.casper_nodes
+            .iter()
+            .find(|node| node.state == NodeState::Running)
+            .ok_or(CctlError::NoRunningNode)?
+            .status()
+            .await?
+            .last_added_block_info
+            .ok_or(CctlError::UnexpectedResponse(
+                "The node didn't add any block yet".to_string(),
+            ))
+    }
+
+    /// Polls the latest block until it satisfies `reached`, failures to fetch it are retried.
+    /// `reached` sees every fetched block, so it may derive its target from the first one.
+    async fn await_block(
+        &self,
+        operation: String,
+        timeout: Duration,
+        mut reached: impl FnMut(&LastAddedBlockInfo) -> bool,
+    ) -> Result<LastAddedBlockInfo, CctlError> {
+        let start = Instant::now();
+        let poll = async {
+            loop {
+                match self.last_added_block().await {
+                    Ok(block) if reached(&block) => return block,
+                    Ok(block) => tracing::debug!(
+                        "At block {} in era {} while {operation}",
+                        block.height,
+                        block.era_id
+                    ),
+                    Err(err) => {
+                        tracing::debug!("Failed to get the latest block while {operation}: {err}")
+                    }
+                }
+                tokio::time::sleep(CHAIN_POLL_INTERVAL).await;
+            }
+        };
+        let result = tokio::time::timeout(timeout, poll).await;
+        result.map_err(|_| CctlError::Timeout {
+            operation,
+            elapsed: start.elapsed(),
+        })
+    }
+
+    /// The directory cctl generated the network's assets, e.g. keys and node configs, into
+    pub fn assets_dir(&self) -> PathBuf {
+        self.working_dir.join("assets")
+    }
+
+    /// Loads all funded test accounts cctl generated, ordered by their index
+    pub fn users(&self) -> Result<Vec<CctlUser>, CctlError> {
+        let assets_dir = self.assets_dir();
+        accounts::user_indices(&assets_dir)?
+            .into_iter()
+            .map(|index| CctlUser::load(&assets_dir, index))
+            .collect()
+    }
+
+    /// Loads the funded test account `user-<index>` cctl generated, indices start at 1
+    pub fn user(&self, index: u8) -> Result<CctlUser, CctlError> {
+        CctlUser::load(&self.assets_dir(), index)
+    }
+
+    /// Loads the keys generated for the [`GenesisAccount::generated`] accounts, in the order
+    /// they were added to the network
+    pub fn genesis_accounts(&self) -> Result<Vec<AccountKeys>, CctlError> {
+        self.genesis_account_dirs
+            .iter()
+            .map(|keys_dir| AccountKeys::load(keys_dir))
+            .collect()
+    }
+
+    /// Loads the keys of the faucet account, which holds a large balance to fund other accounts
+    pub fn faucet(&self) -> Result<AccountKeys, CctlError> {
+        AccountKeys::load(&accounts::faucet_keys_dir(&self.assets_dir()))
+    }
+
+    /// The chain name deploys and transactions need to be signed for, taken from the network's chainspec
+    pub fn chain_name(&self) -> &str {
+        &self.chain_name
+    }
+
+    /// Stops the node with the given id and waits until its process is stopped
+    pub async fn stop_node(&mut self, node_id: u8) -> Result<(), CctlError> {
+        self.control(Process::Node(node_id), "stop", NodeState::Stopped)
+            .await
+    }
+
+    /// Starts the node with the given id and waits until its process is running
+    pub async fn start_node(&mut self, node_id: u8) -> Result<(), CctlError> {
+        self.control(Process::Node(node_id), "start", NodeState::Running)
+            .await
+    }
+
+    /// Restarts the node with the given id and waits until its process is running again
+    pub async fn restart_node(&mut self, node_id: u8) -> Result<(), CctlError> {
+        self.control(Process::Node(node_id), "restart", NodeState::Running)
+            .await
+    }
+
+    /// Stops the sidecar of the node with the given id and waits until its process is stopped
+    pub async fn stop_sidecar(&mut self, node_id: u8) -> Result<(), CctlError> {
+        self.control(Process::Sidecar(node_id), "stop", NodeState::Stopped)
+            .await
+    }
+
+    /// Starts the sidecar of the node with the given id and waits until its process is running
+    pub async fn start_sidecar(&mut self, node_id: u8) -> Result<(), CctlError> {
+        self.control(Process::Sidecar(node_id), "start", NodeState::Running)
+            .await
+    }
+
+    /// Restarts the sidecar of the node with the given id and waits until its process is running again
+    pub async fn restart_sidecar(&mut self, node_id: u8) -> Result<(), CctlError> {
+        self.control(Process::Sidecar(node_id), "restart", NodeState::Running)
+            .await
+    }
+
+    /// Starts the stopped nodes and sidecars of a validator group, e.g. group 3 which cctl
+    /// doesn't start at genesis, and waits until each of its nodes reports that it keeps up
+    /// with or validates the chain at the tip seen by the other running nodes.
+    /// `timeout` bounds the lookup of that tip and the wait for the group's nodes.
+    pub async fn start_validator_group(
+        &mut self,
+        validator_group_id: u8,
+        timeout: Duration,
+    ) -> Result<(), CctlError> {
+        let node_ids = self
+            .casper_nodes
+            .iter()
+            .filter(|node| node.validator_group_id == validator_group_id)
+            .map(|node| node.id)
+            .collect::<Vec<_>>();
+        if node_ids.is_empty() {
+            return Err(CctlError::ValidatorGroupNotFound { validator_group_id });
+        }
+        let start = Instant::now();
+        let deadline = start + timeout;
+        let tip_node = self
+            .casper_nodes
+            .iter()
+            .find(|node| {
+                node.state == NodeState::Running && node.validator_group_id != validator_group_id
+            })
+            .ok_or(CctlError::NoRunningNode)?;
+        let tip_height = tokio::time::timeout_at(deadline.into(), tip_node.status())
+            .await
+            .map_err(|_| CctlError::Timeout {
+                operation: format!("getting the block height of node {}", tip_node.id),
+                elapsed: start.elapsed(),
+            })??
+            .last_added_block_info
+            .map_or(0, |block| block.height);
+
+        tracing::info!("Starting validator group {validator_group_id}");
+        for &node_id in &node_ids {
+            let node_state = self.status_mut(Process::Node(node_id))?.0;
+            if *node_state != NodeState::Running {
+                self.start_node(node_id).await?;
+            }
+            if let Ok((sidecar_state, _)) = self.status_mut(Process::Sidecar(node_id)) {
+                if *sidecar_state != NodeState::Running {
+                    self.start_sidecar(node_id).await?;
+                }
+            }
+        }
+
+        tracing::info!(
+            "Waiting for validator group {validator_group_id} to sync to block {tip_height}"
+        );
+        for node in self
+            .casper_nodes
+            .iter()
+            .filter(|node| node.validator_group_id == validator_group_id)
+        {
+            poll_until(deadline, || async {
+                let status = node.status().await.map_err(|err| err.to_string())?;
+                let height = status.last_added_block_info.map(|block| block.height);
+                match status.reactor_state {
+                    ReactorState::KeepUp | ReactorState::Validate
+                        if height.is_some_and(|height| height >= tip_height) =>
+                    {
+                        Ok(())
+                    }
+                    reactor_state => Err(format!(
+                        "reactor state is {reactor_state:?} at block {height:?} of {tip_height}"
+                    )),
+                }
+            })
+            .await
+            .map_err(|last_error| CctlError::NodeNotReady {
+                node_id: node.id,
+                timeout,
+                last_error,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Updates the state of every node and sidecar with the one the process supervisor
+    /// currently reports, e.g. to detect that a node crashed
+    pub async fn refresh_status(&mut self) -> Result<(), CctlError> {
+        for node_type in self.process_states().await? {
+            let (process, state, info) = node_type.into();
+            match self.status_mut(process) {
+                Ok((current_state, current_info)) => {
+                    *current_state = state;
+                    *current_info = info;
+                }
+                Err(_) => tracing::warn!("Ignoring the state of unknown {process}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the cctl script applying `action` to `process`, then waits for the process to
+    /// reach `target_state` and records it
+    async fn control(
+        &mut self,
+        process: Process,
+        action: &str,
+        target_state: NodeState,
+    ) -> Result<(), CctlError> {
+        // Fail before running any script if the process is unknown
+        self.status_mut(process)?;
+
+        tracing::info!("Running {action} on {process}");
+        run_script(
+            self.runner.as_ref(),
+            &self.assets_dir(),
+            &self.script_env,
+            &process.script(action),
+            &[format!("node={}", process.node_id())],
+            self.script_timeout,
+        )
+        .await?;
+        let info = self.await_process_state(process, target_state).await?;
+        let (state, current_info) = self.status_mut(process)?;
+        *state = target_state;
+        *current_info = info;
+        Ok(())
+    }
+
+    fn status_mut(
+        &mut self,
+        process: Process,
+    ) -> Result<(&mut NodeState, &mut ProcessInfo), CctlError> {
+        match process {
+            Process::Node(node_id) => self
+                .casper_nodes
+                .iter_mut()
+                .find(|node| node.id == node_id)
+                .map(|node| (&mut node.state, &mut node.process))
+                .ok_or(CctlError::NodeNotFound { node_id }),
+            Process::Sidecar(node_id) => self
+                .casper_sidecars
+                .iter_mut()
+                .find(|sidecar| sidecar.id == node_id)
+                .map(|sidecar| (&mut sidecar.state, &mut sidecar.process))
+                .ok_or(CctlError::SidecarNotFound { node_id }),
+        }
+    }
+
+    /// Polls `cctl-infra-net-status` until `process` is reported in `target_state`,
+    /// returning the process' details at that point
+    async fn await_process_state(
+        &self,
+        process: Process,
+        target_state: NodeState,
+    ) -> Result<ProcessInfo, CctlError> {
+        let start = Instant::now();
+        loop {
+            let reached = self
+                .process_states()
+                .await?
+                .into_iter()
+                .map(<(Process, NodeState, ProcessInfo)>::from)
+                .find(|(reported, state, _)| *reported == process && *state == target_state);
+            if let Some((_, _, info)) = reached {
+                return Ok(info);
+            }
+            if start.elapsed() > PROCESS_STATE_TIMEOUT {
+                return Err(CctlError::Timeout {
+                    operation: format!("waiting for {process} to be {target_state:?}"),
+                    elapsed: start.elapsed(),
+                });
+            }
+            tokio::time::sleep(PROCESS_STATE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// The current state of all node and sidecar processes as reported by cctl
+    async fn process_states(&self) -> Result<Vec<RawNodeType>, CctlError> {
+        let output = run_script(
+            self.runner.as_ref(),
+            &self.assets_dir(),
+            &self.script_env,
+            "cctl-infra-net-status",
+            &[],
+            self.script_timeout,
+        )
+        .await?
+        .stdout;
+        let (_, node_types) = parsers::parse_cctl_infra_net_start_lines(&output)
+            .map_err(|_| parse_error("cctl-infra-net-status", &output))?;
+        Ok(node_types)
+    }
+
+    /// Stops the network and removes its temporary working directory, reporting whether
+    /// `cctl-infra-net-stop` succeeded.
+    ///
+    /// Dropping the network stops it as well, but can only log failures.
+    pub async fn shutdown(mut self) -> Result<(), CctlError> {
+        let result = self.stop().await;
+        self.stopped = true;
+        if let Err(err) = result {
+            // The network might still be using its working directory
+            self.keep_working_dir = true;
+            self.remove_working_dir()?;
+            return Err(err);
+        }
+        self.remove_working_dir()
+    }
+
+    async fn stop(&self) -> Result<(), CctlError> {
+        tracing::info!("Stopping the network");
+        run_script(
+            self.runner.as_ref(),
+            &self.assets_dir(),
+            &self.script_env,
+            "cctl-infra-net-stop",
+            &[],
+            self.script_timeout,
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Removes the temporary working directory, unless it should be kept for debugging
+    fn remove_working_dir(&mut self) -> Result<(), CctlError> {
+        let Some(temp_dir) = self.temp_dir.take() else {
+            return Ok(());
+        };
+        if self.keep_working_dir || std::thread::panicking() {
+            let working_dir = temp_dir.into_path();
+            tracing::info!("Keeping working directory: {:?}", working_dir);
+            return Ok(());
+        }
+        tracing::info!("Removing working directory: {:?}", temp_dir.path());
+        Ok(temp_dir.close()?)
     }
     /// Get the deployed contract hash for a hash_name that was passed to new_contract
     /// https://docs.rs/casper-contract/latest/casper_contract/contract_api/storage/fn.new_contract.html
-    pub fn get_contract_hash_for(&self, hash_name: &str) -> ContractHash {
+    pub fn get_contract_hash_for(&self, hash_name: &str) -> Result<ContractHash, CctlError> {
         let contract_hash_path = self.working_dir.join("contracts").join(hash_name);
-        let contract_hash_string = fs::read_to_string(contract_hash_path).unwrap();
-        let contract_hash_bytes = <[u8; 32]>::from_hex(contract_hash_string).unwrap();
-        ContractHash::new(contract_hash_bytes)
+        let contract_hash_string = fs::read_to_string(&contract_hash_path)?;
+        let contract_hash_bytes =
+            <[u8; 32]>::from_hex(contract_hash_string.trim()).map_err(|err| {
+                CctlError::UnexpectedResponse(format!(
+                    "Invalid contract hash in {}: {err}",
+                    contract_hash_path.display()
+                ))
+            })?;
+        Ok(ContractHash::new(contract_hash_bytes))
+    }
+}
+
+/// Runs the probe until it succeeds, returning its last error once the deadline passed.
+/// A probe still running at the deadline is cancelled.
+async fn poll_until<F, Fut>(deadline: Instant, mut probe: F) -> Result<(), String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let mut last_error = None;
+    loop {
+        match tokio::time::timeout_at(deadline.into(), probe()).await {
+            Ok(Ok(())) => return Ok(()),
+            Ok(Err(err)) if Instant::now() >= deadline => return Err(err),
+            Ok(Err(err)) => {
+                tracing::debug!("Not ready yet: {err}");
+                last_error = Some(err);
+            }
+            Err(_) => {
+                return Err(last_error.unwrap_or_else(|| "no answer in time".to_string()));
+            }
+        }
+        tokio::time::sleep(READINESS_POLL_INTERVAL).await;
+    }
+}
+
+fn parse_error(script: &str, input: &str) -> CctlError {
+    CctlError::ParseError {
+        script: script.to_string(),
+        input: input.to_string(),
     }
 }
 
 impl Drop for CCTLNetwork {
     fn drop(&mut self) {
-        let output = Command::new("cctl-infra-net-stop")
-            .env("CCTL_ASSETS", self.working_dir.join("assets"))
-            .output()
-            .expect("Failed to stop the network");
-        io::stdout().write_all(&output.stdout).unwrap();
-        io::stderr().write_all(&output.stderr).unwrap();
+        if self.stopped {
+            return;
+        }
+        if let Err(err) = block_on(self.stop()) {
+            tracing::error!("Failed to stop the network: {err}");
+            // The network might still be using its working directory
+            self.keep_working_dir = true;
+        }
+        if let Err(err) = self.remove_working_dir() {
+            tracing::error!("Failed to remove the working directory: {err}");
+        }
     }
 }
 
-/// Deploys a contract as the given user for the contract's defined hash name located at the path.
+/// Deploys a contract as the given account for the contract's defined hash name located at the path.
 /// The hash name should be equal to the hash name passed to https://docs.rs/casper-contract/latest/casper_contract/contract_api/storage/fn.new_locked_contract.html
 async fn deploy_contract(
     casper_node_rpc_url: &str,
+    chain_name: &str,
     contract_deployer_skey: &SecretKey,
     contract_deployer_addr: &AccountHash,
     DeployableContract {
         hash_name,
         runtime_args,
         path,
+        ..
     }: &DeployableContract,
-) -> anyhow::Result<(String, ContractHash)> {
-    tracing::info!(
-        "Deploying contract '{}': {}",
-        &hash_name,
-        path.to_str().unwrap()
-    );
+    max_contract_init_wait_time: Duration,
+) -> Result<(String, ContractHash), CctlError> {
+    tracing::info!("Deploying contract '{}': {}", &hash_name, path.display());
 
     let casper_client_verbosity = casper_client_verbosity();
 
     let contract_bytes = fs::read(path)?;
     let runtime_args = runtime_args.clone().unwrap_or(runtime_args! {});
     let contract = ExecutableDeployItem::new_module_bytes(contract_bytes.into(), runtime_args);
-    let deploy = DeployBuilder::new(
-        // TODO ideally make the chain-name configurable
-        "cspr-dev-cctl",
-        contract,
-    )
-    .with_secret_key(contract_deployer_skey)
-    .with_standard_payment(MAX_GAS_FEE_PAYMENT_AMOUNT) // max amount allowed to be used on gas fees
-    .with_timestamp(Timestamp::now())
-    .with_ttl(TimeDiff::from_millis(60_000)) // 1 min
-    .build()?;
+    let deploy = DeployBuilder::new(chain_name, contract)
+        .with_secret_key(contract_deployer_skey)
+        .with_standard_payment(MAX_GAS_FEE_PAYMENT_AMOUNT) // max amount allowed to be used on gas fees
+        .with_timestamp(Timestamp::now())
+        .with_ttl(TimeDiff::from_millis(60_000)) // 1 min
+        .build()
+        .map_err(|err| CctlError::DeployBuild(err.to_string()))?;
 
     tracing::info!("Submitting contract deploy");
     let deploy_hash = put_deploy(
@@ -322,68 +1221,23 @@ async fn deploy_contract(
         deploy,
     )
     .await
-    .map_err(Into::<anyhow::Error>::into)
     .map(|response| response.result.deploy_hash)?;
 
-    const MAX_CONTRACT_INIT_WAIT_TIME: Duration = Duration::from_secs(60);
     tracing::info!(
-        "Waiting {MAX_CONTRACT_INIT_WAIT_TIME:?} for successful contract initialization"
+        "Waiting {max_contract_init_wait_time:?} for successful contract initialization"
     );
-    let start = Instant::now();
-    retry(ExponentialBackoff::default(), || async {
-        let timed_out = start.elapsed() > MAX_CONTRACT_INIT_WAIT_TIME;
-
-        let response = get_deploy(
-            JsonRpcId::Number(1),
-            casper_node_rpc_url,
-            casper_client_verbosity,
-            deploy_hash,
-            false,
-        )
-        .await
-        .map_err(|err| {
-            let elapsed = start.elapsed().as_secs();
-            tracing::info!("Waited {elapsed}s for successful contract initialization, the last reported error was: {err:?}");
-            err
-        })
-        .map_err(|err| match &err {
-            err if timed_out => backoff::Error::permanent(anyhow!("Timeout on error: {err:?}")),
-            Error::ResponseIsHttpError { .. } | Error::FailedToGetResponse { .. } => {
-                backoff::Error::transient(anyhow!(err))
-            }
-            _ => backoff::Error::permanent(anyhow!(err)),
-        })?;
-
-        match response.result.execution_info {
-            Some(execution_info) => match execution_info.execution_result {
-                Some(execution_result) => match &execution_result {
-                    ExecutionResult::V1(execution_result_v1) => match execution_result_v1 {
-                        ExecutionResultV1::Failure { error_message, .. } => {
-                            Err(backoff::Error::permanent(anyhow!(error_message.clone())))
-                        }
-                        ExecutionResultV1::Success { .. } => Ok(()),
-                    }
-                    ExecutionResult::V2(execution_result_v2) => match &execution_result_v2.error_message {
-                        None => Ok(()),
-                        Some(error_message) => Err(backoff::Error::permanent(anyhow!(error_message.clone())))
-                    }
-                }
-                None if timed_out => Err(backoff::Error::permanent(anyhow!(
-                    "Timeout on error: No execution result"
-                ))),
-                None => Err(backoff::Error::transient(anyhow!(
-                    "No execution results there yet"
-                ))),
-            },
-            None if timed_out => Err(backoff::Error::permanent(anyhow!(
-                "Timeout on error: No execution info"
-            ))),
-            None => Err(backoff::Error::transient(anyhow!(
-                "No execution results there yet"
-            ))),
-        }
-    })
+    let outcome = await_execution(
+        casper_node_rpc_url,
+        deploy_hash.into(),
+        max_contract_init_wait_time,
+    )
     .await?;
+    if let Some(message) = outcome.error_message {
+        return Err(CctlError::DeployFailed {
+            deploy_hash,
+            message,
+        });
+    }
     tracing::info!("Contract was deployed successfully");
 
     tracing::info!("Fetching deployed contract hash");
@@ -394,17 +1248,15 @@ async fn deploy_contract(
         casper_client_verbosity,
         Option::None,
     )
-    .await
-    .map_err(Into::<anyhow::Error>::into)
-    .and_then(|response| {
-        response
-            .result
-            .state_root_hash
-            .ok_or(anyhow!("No state root hash present in response"))
-    })?;
+    .await?
+    .result
+    .state_root_hash
+    .ok_or(CctlError::UnexpectedResponse(
+        "No state root hash present in response".to_string(),
+    ))?;
 
     tracing::info!("Querying global state");
-    let contract_hash: ContractHash = query_global_state(
+    let response = query_global_state(
         JsonRpcId::Number(1),
         casper_node_rpc_url,
         casper_client_verbosity,
@@ -412,31 +1264,955 @@ async fn deploy_contract(
         Key::AddressableEntity(casper_types::EntityAddr::Account(contract_deployer_addr.0)),
         vec![hash_name.clone()],
     )
-    .await
-    .map_err(Into::<anyhow::Error>::into)
-    .and_then(|response| match response.result.stored_value {
-        StoredValue::Package(package) => Ok(ContractHash::from(
+    .await?;
+    let no_contract_version =
+        || CctlError::UnexpectedResponse("Expected at least one contract version".to_string());
+    let contract_hash: ContractHash = match response.result.stored_value {
+        StoredValue::Package(package) => ContractHash::from(
             package
                 .versions()
                 .contract_hashes()
                 .next()
-                .expect("")
+                .ok_or_else(no_contract_version)?
                 .value(),
-        )),
-        StoredValue::ContractPackage(contract_package) => Ok(*contract_package
+        ),
+        StoredValue::ContractPackage(contract_package) => *contract_package
             .versions()
             .values()
             .next()
-            .expect("Expected at least one contract version")),
-        other => Err(anyhow!(
-            "Unexpected result type, type is not a CLValue: {:?}",
-            other
-        )),
-    })?;
+            .ok_or_else(no_contract_version)?,
+        other => {
+            return Err(CctlError::UnexpectedResponse(format!(
+                "Unexpected result type, type is not a CLValue: {:?}",
+                other
+            )))
+        }
+    };
     tracing::info!(
         "Successfully fetched the contract hash for {}: {}",
         &hash_name,
         &contract_hash
     );
-    Ok::<(String, ContractHash), anyhow::Error>((hash_name.clone(), contract_hash))
+    Ok((hash_name.clone(), contract_hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NET_START_OUTPUT: &str = r#"
+        2024-08-30T17:15:14.966443 [INFO] [626072] CCTL :: Genesis non-bootstrap nodes -> started
+        validator-group-1:cctl-node-1            RUNNING   pid 626095, uptime 0:00:03
+        validator-group-1:cctl-node-1-sidecar    RUNNING   pid 626096, uptime 0:00:03
+        validator-group-3:cctl-node-2            STOPPED   Not started
+        validator-group-3:cctl-node-2-sidecar    STOPPED   Not started
+        2024-08-30T17:15:15.110310 [INFO] [626072] CCTL :: Network start ends
+    "#;
+
+    const GENESIS_ONLY_NET_START_OUTPUT: &str = r#"
+        2024-08-30T17:15:14.966443 [INFO] [626072] CCTL :: Genesis bootstrap nodes -> started
+        validator-group-1:cctl-node-1            RUNNING   pid 626095, uptime 0:00:03
+        validator-group-1:cctl-node-1-sidecar    RUNNING   pid 626096, uptime 0:00:03
+        validator-group-1:cctl-node-2            RUNNING   pid 626097, uptime 0:00:03
+        validator-group-1:cctl-node-2-sidecar    RUNNING   pid 626098, uptime 0:00:03
+        2024-08-30T17:15:15.110310 [INFO] [626072] CCTL :: Network start ends
+    "#;
+
+    const NODE_PORTS_OUTPUT: &str = r#"
+        2024-09-02T08:44:46.871632 [INFO] [124520] CCTL :: NODE-1
+        2024-09-02T08:44:46.874259 [INFO] [124520] CCTL ::     PROTOCOL ----> 11101
+        2024-09-02T08:44:46.876701 [INFO] [124520] CCTL ::     BINARY ------> 12101
+        2024-09-02T08:44:46.879103 [INFO] [124520] CCTL ::     REST --------> 13101
+        2024-09-02T08:44:46.881573 [INFO] [124520] CCTL ::     SSE ---------> 14101
+        2024-09-02T08:44:46.883303 [INFO] [124520] CCTL :: ------------------------------------------------------------------------------------------------------
+        2024-09-02T08:44:46.889475 [INFO] [124520] CCTL :: NODE-2
+        2024-09-02T08:44:46.891804 [INFO] [124520] CCTL ::     PROTOCOL ----> 11102
+        2024-09-02T08:44:46.894156 [INFO] [124520] CCTL ::     BINARY ------> 12102
+        2024-09-02T08:44:46.896950 [INFO] [124520] CCTL ::     REST --------> 13102
+        2024-09-02T08:44:46.899175 [INFO] [124520] CCTL ::     SSE ---------> 14102
+        2024-09-02T08:44:46.901358 [INFO] [124520] CCTL :: ------------------------------------------------------------------------------------------------------
+    "#;
+
+    const SIDECAR_PORTS_OUTPUT: &str = r#"
+        2024-09-02T09:49:32.804362 [INFO] [194431] CCTL :: SIDECAR-1
+        2024-09-02T09:49:32.807243 [INFO] [194431] CCTL ::     NODE-CLIENT -> 12101
+        2024-09-02T09:49:32.809625 [INFO] [194431] CCTL ::     MAIN-RPC ----> 21101
+        2024-09-02T09:49:32.811288 [INFO] [194431] CCTL ::     SPEC-EXEC ---> 22101
+        2024-09-02T09:49:32.816160 [INFO] [194431] CCTL :: SIDECAR-2
+        2024-09-02T09:49:32.818236 [INFO] [194431] CCTL ::     NODE-CLIENT -> 12102
+        2024-09-02T09:49:32.820258 [INFO] [194431] CCTL ::     MAIN-RPC ----> 21102
+        2024-09-02T09:49:32.822750 [INFO] [194431] CCTL ::     SPEC-EXEC ---> 22102
+    "#;
+
+    fn replay_runner(node_ports_output: &str) -> ReplayCommandRunner {
+        replay_runner_starting(NET_START_OUTPUT, node_ports_output)
+    }
+
+    fn replay_runner_starting(
+        net_start_output: &str,
+        node_ports_output: &str,
+    ) -> ReplayCommandRunner {
+        ReplayCommandRunner::new()
+            .with_stdout("cctl-infra-net-setup", "")
+            .with_stdout("cctl-infra-net-start", net_start_output)
+            .with_stdout("cctl-infra-node-view-ports", node_ports_output)
+            .with_stdout("cctl-infra-sidecar-view-ports", SIDECAR_PORTS_OUTPUT)
+    }
+
+    /// Configures a network replaying the given runner's cctl output, without probing it
+    fn replay_builder(runner: Arc<dyn CctlCommandRunner>) -> CCTLNetworkBuilder {
+        CCTLNetwork::builder()
+            .with_chain_name("cspr-dev-cctl")
+            .with_command_runner(runner)
+            .with_readiness_probe(false)
+    }
+
+    /// Runs a network replaying the given runner's cctl output in `working_dir`
+    async fn replayed_network(
+        runner: Arc<dyn CctlCommandRunner>,
+        working_dir: &Path,
+    ) -> CCTLNetwork {
+        replay_builder(runner)
+            .with_working_dir(working_dir)
+            .run()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_run_matches_nodes_and_sidecars_with_their_ports() {
+        let working_dir = tempdir().unwrap();
+        let runner =
+            Arc::new(replay_runner(NODE_PORTS_OUTPUT).with_stdout("cctl-infra-net-stop", ""));
+
+        let network = replay_builder(runner.clone())
+            .with_working_dir(working_dir.path())
+            .with_chainspec_path("/chainspec.toml")
+            .run()
+            .await
+            .unwrap();
+
+        let nodes = network
+            .casper_nodes
+            .iter()
+            .map(|node| (node.id, node.validator_group_id, node.state, node.port))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (
+                    1,
+                    1,
+                    NodeState::Running,
+                    CasperNodePorts {
+                        protocol_port: 11101,
+                        binary_port: 12101,
+                        rest_port: 13101,
+                        sse_port: 14101,
+                    }
+                ),
+                (
+                    2,
+                    3,
+                    NodeState::Stopped,
+                    CasperNodePorts {
+                        protocol_port: 11102,
+                        binary_port: 12102,
+                        rest_port: 13102,
+                        sse_port: 14102,
+                    }
+                ),
+            ],
+            nodes
+        );
+        let sidecars = network
+            .casper_sidecars
+            .iter()
+            .map(|sidecar| (sidecar.id, sidecar.state, sidecar.port.rpc_port))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (1, NodeState::Running, 21101),
+                (2, NodeState::Stopped, 21102)
+            ],
+            sidecars
+        );
+
+        drop(network);
+        assert_eq!(
+            vec![
+                "cctl-infra-net-setup chainspec=/chainspec.toml".to_string(),
+                "cctl-infra-net-start".to_string(),
+                "cctl-infra-node-view-ports".to_string(),
+                "cctl-infra-sidecar-view-ports".to_string(),
+                "cctl-infra-net-stop".to_string(),
+            ],
+            runner.invocations()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_fails_if_ports_of_a_node_are_missing() {
+        let working_dir = tempdir().unwrap();
+        let node_1_ports_output = NODE_PORTS_OUTPUT.split("NODE-2").next().unwrap();
+        let runner =
+            Arc::new(replay_runner(node_1_ports_output).with_stdout("cctl-infra-net-stop", ""));
+
+        let result = replay_builder(runner)
+            .with_working_dir(working_dir.path())
+            .run()
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CctlError::PortsMissing { node_id: 2 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_run_fails_fast_if_a_script_fails() {
+        let working_dir = tempdir().unwrap();
+        let runner = Arc::new(
+            ReplayCommandRunner::new()
+                .with_stdout("cctl-infra-net-setup", "")
+                .with_output("cctl-infra-net-start", Some(1), "", "supervisord failed")
+                .with_stdout("cctl-infra-net-stop", ""),
+        );
+
+        let result = replay_builder(runner.clone())
+            .with_working_dir(working_dir.path())
+            .run()
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CctlError::ScriptFailed { exit_code: Some(1), ref stderr, .. }) if stderr == "supervisord failed"
+        ));
+        assert_eq!(
+            vec![
+                "cctl-infra-net-setup".to_string(),
+                "cctl-infra-net-start".to_string(),
+                "cctl-infra-net-stop".to_string(),
+            ],
+            runner.invocations()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_passes_the_topology_to_every_script() {
+        let working_dir = tempdir().unwrap();
+        let runner = Arc::new(
+            replay_runner_starting(GENESIS_ONLY_NET_START_OUTPUT, NODE_PORTS_OUTPUT)
+                .with_stdout("cctl-infra-net-stop", ""),
+        );
+
+        let network = replay_builder(runner.clone())
+            .with_working_dir(working_dir.path())
+            .with_topology(Topology::genesis_only(2))
+            .run()
+            .await
+            .unwrap();
+        let nodes = network
+            .casper_nodes
+            .iter()
+            .map(|node| (node.id, node.validator_group_id, node.state))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![(1, 1, NodeState::Running), (2, 1, NodeState::Running)],
+            nodes
+        );
+        drop(network);
+
+        let env =
+            "CCTL_COUNT_OF_NODES=2 CCTL_COUNT_OF_GENESIS_NODES=2 CCTL_COUNT_OF_BOOTSTRAP_NODES=2";
+        assert_eq!(
+            vec![
+                format!("{env} cctl-infra-net-setup"),
+                format!("{env} cctl-infra-net-start"),
+                format!("{env} cctl-infra-node-view-ports"),
+                format!("{env} cctl-infra-sidecar-view-ports"),
+                format!("{env} cctl-infra-net-stop"),
+            ],
+            runner.invocations()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_fails_if_cctl_ignores_the_topology() {
+        let working_dir = tempdir().unwrap();
+        let runner =
+            Arc::new(replay_runner(NODE_PORTS_OUTPUT).with_stdout("cctl-infra-net-stop", ""));
+
+        let result = replay_builder(runner.clone())
+            .with_working_dir(working_dir.path())
+            .with_topology(Topology::genesis_only(2))
+            .run()
+            .await;
+
+        // The replayed cctl started one genesis node and set up another one in group 3
+        assert!(matches!(
+            result,
+            Err(CctlError::TopologyMismatch {
+                actual: Topology {
+                    nodes: 2,
+                    genesis_nodes: 1,
+                    bootstrap_nodes: 1,
+                },
+                ..
+            })
+        ));
+        assert!(runner
+            .invocations()
+            .last()
+            .unwrap()
+            .ends_with("cctl-infra-net-stop"));
+    }
+
+    #[tokio::test]
+    async fn test_run_applies_chainspec_overrides() {
+        let working_dir = tempdir().unwrap();
+        let base_chainspec_path = working_dir.path().join("base-chainspec.toml");
+        fs::write(
+            &base_chainspec_path,
+            "[network]\nname = 'cspr-dev-cctl'\n\n[core]\nminimum_era_height = 5\nera_duration = '41 seconds'\n",
+        )
+        .unwrap();
+        let runner =
+            Arc::new(replay_runner(NODE_PORTS_OUTPUT).with_stdout("cctl-infra-net-stop", ""));
+
+        let network = replay_builder(runner.clone())
+            .with_working_dir(working_dir.path())
+            .with_chainspec_path(&base_chainspec_path)
+            .with_chainspec_overrides(ChainspecOverrides::new().with_minimum_era_height(2))
+            .with_chainspec_overrides(
+                ChainspecOverrides::new().with_era_duration(Duration::from_secs(10)),
+            )
+            .run()
+            .await
+            .unwrap();
+        drop(network);
+
+        let chainspec_path = working_dir.path().join("chainspec.toml");
+        let chainspec = chainspec::read_toml(&chainspec_path).unwrap();
+        assert_eq!(
+            Some(2),
+            chainspec["core"]["minimum_era_height"].as_integer()
+        );
+        assert_eq!(Some("10000 ms"), chainspec["core"]["era_duration"].as_str());
+        // The network is set up once, with the patched chainspec
+        assert_eq!(
+            vec![format!(
+                "cctl-infra-net-setup chainspec={}",
+                chainspec_path.display()
+            )],
+            runner
+                .invocations()
+                .into_iter()
+                .filter(|invocation| invocation.starts_with("cctl-infra-net-setup"))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_genesis_accounts_ignores_keys_of_an_earlier_network() {
+        let working_dir = tempdir().unwrap();
+        fs::create_dir_all(working_dir.path().join("genesis-accounts/account-1")).unwrap();
+        let runner =
+            Arc::new(replay_runner(NODE_PORTS_OUTPUT).with_stdout("cctl-infra-net-stop", ""));
+
+        let network = replayed_network(runner, working_dir.path()).await;
+
+        assert!(network.genesis_accounts().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_with_the_fast_profile() {
+        let working_dir = tempdir().unwrap();
+        let chainspec_path = working_dir.path().join("base-chainspec.toml");
+        fs::write(
+            &chainspec_path,
+            "[core]\nera_duration = '41 seconds'\nminimum_era_height = 10\nminimum_block_time = '16384 ms'\n",
+        )
+        .unwrap();
+        let single_node_net_start_output = NET_START_OUTPUT
+            .lines()
+            .filter(|line| !line.contains("cctl-node-2"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let runner = Arc::new(
+            replay_runner_starting(&single_node_net_start_output, NODE_PORTS_OUTPUT)
+                .with_stdout("cctl-infra-net-stop", ""),
+        );
+
+        let network = replay_builder(runner.clone())
+            .with_working_dir(working_dir.path())
+            .with_chainspec_path(&chainspec_path)
+            .with_profile(NetworkProfile::Fast)
+            .with_chainspec_overrides(ChainspecOverrides::new().with_minimum_era_height(3))
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(1, network.casper_nodes.len());
+        drop(network);
+
+        let chainspec = chainspec::read_toml(&working_dir.path().join("chainspec.toml")).unwrap();
+        let expected: toml::Table =
+            "[core]\nera_duration = '10000 ms'\nminimum_era_height = 3\nminimum_block_time = '1000 ms'\n"
+                .parse()
+                .unwrap();
+        assert_eq!(expected, chainspec);
+        assert!(runner.invocations()[0].starts_with(
+            "CCTL_COUNT_OF_NODES=1 CCTL_COUNT_OF_GENESIS_NODES=1 CCTL_COUNT_OF_BOOTSTRAP_NODES=1 cctl-infra-net-setup"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_run_rejects_an_invalid_topology() {
+        let runner = Arc::new(replay_runner(NODE_PORTS_OUTPUT));
+
+        let result = replay_builder(runner.clone())
+            .with_topology(Topology {
+                nodes: 1,
+                genesis_nodes: 2,
+                bootstrap_nodes: 1,
+            })
+            .run()
+            .await;
+
+        assert!(matches!(result, Err(CctlError::InvalidConfig(_))));
+        assert!(runner.invocations().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_fails_if_a_node_does_not_become_ready() {
+        let working_dir = tempdir().unwrap();
+        let rest_port = serve_node_status(1, 0).await;
+        let free_port = std::net::TcpListener::bind("0.0.0.0:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let runner = Arc::new(
+            replay_runner_starting(
+                GENESIS_ONLY_NET_START_OUTPUT,
+                &NODE_PORTS_OUTPUT
+                    .replace("13101", &rest_port.to_string())
+                    .replace("13102", &free_port.to_string()),
+            )
+            .with_stdout("cctl-infra-net-stop", ""),
+        );
+
+        let result = replay_builder(runner.clone())
+            .with_working_dir(working_dir.path())
+            .with_readiness_probe(true)
+            .with_script_timeout(Duration::from_secs(5))
+            .with_readiness_timeout(Duration::from_millis(500))
+            .run()
+            .await;
+
+        // Node 1 reports block 1, but nothing listens on the replayed REST port of node 2
+        assert!(matches!(
+            result,
+            Err(CctlError::NodeNotReady { node_id: 2, .. })
+        ));
+        assert_eq!(
+            Some("cctl-infra-net-stop".to_string()),
+            runner.invocations().pop()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_fails_if_a_node_never_answers() {
+        let working_dir = tempdir().unwrap();
+        let rest_port = serve_node_status(1, 0).await;
+        let runner = Arc::new(
+            replay_runner_starting(
+                GENESIS_ONLY_NET_START_OUTPUT,
+                &NODE_PORTS_OUTPUT
+                    .replace("13101", &rest_port.to_string())
+                    .replace("13102", &serve_no_answer().await.to_string()),
+            )
+            .with_stdout("cctl-infra-net-stop", ""),
+        );
+
+        let result = replay_builder(runner)
+            .with_working_dir(working_dir.path())
+            .with_readiness_probe(true)
+            .with_script_timeout(Duration::from_secs(5))
+            .with_readiness_timeout(Duration::from_millis(500))
+            .run()
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CctlError::NodeNotReady { node_id: 2, .. })
+        ));
+    }
+
+    /// Accepts connections without ever answering, returns the port it listens on
+    async fn serve_no_answer() -> u16 {
+        let listener = tokio::net::TcpListener::bind("0.0.0.0:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+        port
+    }
+
+    /// Answers every request with the given node status, returns the port it listens on
+    async fn serve_node_status(height: u64, era_id: u64) -> u16 {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let body = format!(
+            r#"{{"chainspec_name":"cspr-dev-cctl","reactor_state":"Validate","build_version":"2.0.0","uptime":"1m","last_added_block_info":{{"hash":"aa","timestamp":"2024-09-02T09:49:32.804Z","era_id":{era_id},"height":{height},"state_root_hash":"bb"}}}}"#
+        );
+        let listener = tokio::net::TcpListener::bind("0.0.0.0:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn test_await_block_height_and_era() {
+        let working_dir = tempdir().unwrap();
+        let rest_port = serve_node_status(5, 1).await;
+        let runner = Arc::new(
+            replay_runner(&NODE_PORTS_OUTPUT.replace("13101", &rest_port.to_string()))
+                .with_stdout("cctl-infra-net-stop", ""),
+        );
+
+        let network = replayed_network(runner, working_dir.path()).await;
+
+        let timeout = Duration::from_secs(5);
+        assert_eq!(
+            5,
+            network.await_block_height(3, timeout).await.unwrap().height
+        );
+        assert_eq!(1, network.await_era(1, timeout).await.unwrap().era_id);
+        assert!(matches!(
+            network.await_blocks(1, Duration::ZERO).await,
+            Err(CctlError::Timeout { .. })
+        ));
+        assert!(matches!(
+            network.await_next_era(Duration::ZERO).await,
+            Err(CctlError::Timeout { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_await_block_times_out_if_the_node_never_answers() {
+        let working_dir = tempdir().unwrap();
+        let runner = Arc::new(
+            replay_runner(
+                &NODE_PORTS_OUTPUT.replace("13101", &serve_no_answer().await.to_string()),
+            )
+            .with_stdout("cctl-infra-net-stop", ""),
+        );
+
+        let network = replayed_network(runner, working_dir.path()).await;
+
+        let timeout = Duration::from_millis(500);
+        assert!(matches!(
+            network.await_block_height(1, timeout).await,
+            Err(CctlError::Timeout { .. })
+        ));
+        assert!(matches!(
+            network.await_blocks(1, timeout).await,
+            Err(CctlError::Timeout { .. })
+        ));
+        assert!(matches!(
+            network.await_next_era(timeout).await,
+            Err(CctlError::Timeout { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_reports_stop_failures_and_does_not_stop_again() {
+        let working_dir = tempdir().unwrap();
+        let runner = Arc::new(replay_runner(NODE_PORTS_OUTPUT).with_output(
+            "cctl-infra-net-stop",
+            Some(1),
+            "",
+            "supervisorctl not reachable",
+        ));
+
+        let network = replayed_network(runner.clone(), working_dir.path()).await;
+        let result = network.shutdown().await;
+
+        assert!(matches!(
+            result,
+            Err(CctlError::ScriptFailed {
+                exit_code: Some(1),
+                ..
+            })
+        ));
+        let stop_invocations = runner
+            .invocations()
+            .into_iter()
+            .filter(|invocation| invocation == "cctl-infra-net-stop")
+            .count();
+        assert_eq!(1, stop_invocations);
+    }
+
+    /// Replays the recorded outputs and remembers the assets directory of the last script run
+    struct AssetsDirRecorder {
+        replay: ReplayCommandRunner,
+        assets_dir: std::sync::Mutex<Option<PathBuf>>,
+    }
+
+    #[async_trait::async_trait]
+    impl CctlCommandRunner for AssetsDirRecorder {
+        async fn run(
+            &self,
+            assets_dir: &Path,
+            env: &[(String, String)],
+            script: &str,
+            args: &[String],
+        ) -> Result<ScriptOutput, CctlError> {
+            *self.assets_dir.lock().unwrap() = Some(assets_dir.to_path_buf());
+            self.replay.run(assets_dir, env, script, args).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_keeps_the_temporary_working_dir_if_setup_fails() {
+        let runner = Arc::new(AssetsDirRecorder {
+            replay: ReplayCommandRunner::new().with_output(
+                "cctl-infra-net-setup",
+                Some(1),
+                "",
+                "setup failed",
+            ),
+            assets_dir: Default::default(),
+        });
+
+        let result = replay_builder(runner.clone()).run().await;
+
+        assert!(matches!(result, Err(CctlError::ScriptFailed { .. })));
+        let assets_dir = runner.assets_dir.lock().unwrap().clone().unwrap();
+        let working_dir = assets_dir.parent().unwrap();
+        assert!(working_dir.exists());
+        fs::remove_dir_all(working_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_keeps_the_temporary_working_dir_if_stopping_fails() {
+        let runner = Arc::new(replay_runner(NODE_PORTS_OUTPUT).with_output(
+            "cctl-infra-net-stop",
+            Some(1),
+            "",
+            "supervisorctl not reachable",
+        ));
+
+        let network = replay_builder(runner).run().await.unwrap();
+        let working_dir = network.working_dir.clone();
+        assert!(network.shutdown().await.is_err());
+
+        assert!(working_dir.exists());
+        fs::remove_dir_all(working_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_removes_the_temporary_working_dir() {
+        let runner =
+            Arc::new(replay_runner(NODE_PORTS_OUTPUT).with_stdout("cctl-infra-net-stop", ""));
+
+        let network = replay_builder(runner).run().await.unwrap();
+        let working_dir = network.working_dir.clone();
+        assert!(working_dir.exists());
+        network.shutdown().await.unwrap();
+
+        assert!(!working_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_keeps_the_temporary_working_dir_if_requested() {
+        let runner =
+            Arc::new(replay_runner(NODE_PORTS_OUTPUT).with_stdout("cctl-infra-net-stop", ""));
+
+        let network = replay_builder(runner)
+            .with_keep_working_dir(true)
+            .run()
+            .await
+            .unwrap();
+        let working_dir = network.working_dir.clone();
+        network.shutdown().await.unwrap();
+
+        assert!(working_dir.exists());
+        fs::remove_dir_all(working_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stop_and_start_node() {
+        let working_dir = tempdir().unwrap();
+        let runner = Arc::new(
+            replay_runner(NODE_PORTS_OUTPUT)
+                .with_stdout("cctl-infra-node-stop", "")
+                .with_stdout("cctl-infra-node-start", "")
+                .with_stdout(
+                    "cctl-infra-net-status",
+                    "validator-group-1:cctl-node-1            STOPPED   Sep 02 09:49 AM\n",
+                )
+                .with_stdout(
+                    "cctl-infra-net-status",
+                    "validator-group-1:cctl-node-1            RUNNING   pid 626095, uptime 0:00:01\n",
+                )
+                .with_stdout("cctl-infra-net-stop", ""),
+        );
+
+        let mut network = replayed_network(runner.clone(), working_dir.path()).await;
+
+        network.stop_node(1).await.unwrap();
+        assert_eq!(NodeState::Stopped, network.casper_nodes[0].state);
+        network.start_node(1).await.unwrap();
+        assert_eq!(NodeState::Running, network.casper_nodes[0].state);
+        // The sidecar isn't affected
+        assert_eq!(NodeState::Running, network.casper_sidecars[0].state);
+
+        network.shutdown().await.unwrap();
+        assert_eq!(
+            vec![
+                "cctl-infra-node-stop node=1".to_string(),
+                "cctl-infra-net-status".to_string(),
+                "cctl-infra-node-start node=1".to_string(),
+                "cctl-infra-net-status".to_string(),
+                "cctl-infra-net-stop".to_string(),
+            ],
+            runner.invocations()[4..]
+        );
+    }
+
+    /// Replays a network whose node 1 is at block 5 and whose node 2 in validator group 3
+    /// reports `node_2_height` once started
+    async fn validator_group_runner(node_2_height: u64) -> ReplayCommandRunner {
+        let node_1_rest_port = serve_node_status(5, 1).await;
+        let node_2_rest_port = serve_node_status(node_2_height, 1).await;
+        let node_ports_output = NODE_PORTS_OUTPUT
+            .replace("13101", &node_1_rest_port.to_string())
+            .replace("13102", &node_2_rest_port.to_string());
+        replay_runner(&node_ports_output)
+            .with_stdout("cctl-infra-node-start", "")
+            .with_stdout("cctl-infra-sidecar-start", "")
+            .with_stdout(
+                "cctl-infra-net-status",
+                r#"
+                validator-group-1:cctl-node-1            RUNNING   pid 626095, uptime 0:01:03
+                validator-group-1:cctl-node-1-sidecar    RUNNING   pid 626096, uptime 0:01:03
+                validator-group-3:cctl-node-2            RUNNING   pid 626097, uptime 0:00:01
+                validator-group-3:cctl-node-2-sidecar    RUNNING   pid 626098, uptime 0:00:01
+                "#,
+            )
+            .with_stdout("cctl-infra-net-stop", "")
+    }
+
+    #[tokio::test]
+    async fn test_start_validator_group() {
+        let working_dir = tempdir().unwrap();
+        let runner = Arc::new(validator_group_runner(5).await);
+
+        let mut network = replayed_network(runner.clone(), working_dir.path()).await;
+        network
+            .start_validator_group(3, Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        assert_eq!(NodeState::Running, network.casper_nodes[1].state);
+        assert_eq!(
+            ProcessInfo::Running {
+                pid: 626097,
+                uptime: Duration::from_secs(1)
+            },
+            network.casper_nodes[1].process
+        );
+        assert_eq!(NodeState::Running, network.casper_sidecars[1].state);
+        network.shutdown().await.unwrap();
+        assert_eq!(
+            vec![
+                "cctl-infra-node-start node=2".to_string(),
+                "cctl-infra-net-status".to_string(),
+                "cctl-infra-sidecar-start node=2".to_string(),
+                "cctl-infra-net-status".to_string(),
+                "cctl-infra-net-stop".to_string(),
+            ],
+            runner.invocations()[4..]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_validator_group_fails_if_a_node_does_not_sync() {
+        let working_dir = tempdir().unwrap();
+        let runner = Arc::new(validator_group_runner(3).await);
+
+        let mut network = replayed_network(runner, working_dir.path()).await;
+
+        assert!(matches!(
+            network.start_validator_group(3, Duration::from_millis(500)).await,
+            Err(CctlError::NodeNotReady { node_id: 2, ref last_error, .. })
+                if last_error == "reactor state is Validate at block Some(3) of 5"
+        ));
+        // The node was started nevertheless
+        assert_eq!(NodeState::Running, network.casper_nodes[1].state);
+    }
+
+    #[tokio::test]
+    async fn test_start_validator_group_fails_if_the_tip_is_unknown() {
+        let working_dir = tempdir().unwrap();
+        let runner = Arc::new(
+            replay_runner(
+                &NODE_PORTS_OUTPUT.replace("13101", &serve_no_answer().await.to_string()),
+            )
+            .with_stdout("cctl-infra-net-stop", ""),
+        );
+
+        let mut network = replayed_network(runner, working_dir.path()).await;
+
+        // Node 1 never answers its status requests
+        assert!(matches!(
+            network
+                .start_validator_group(3, Duration::from_millis(500))
+                .await,
+            Err(CctlError::Timeout { .. })
+        ));
+        assert_eq!(NodeState::Stopped, network.casper_nodes[1].state);
+    }
+
+    #[tokio::test]
+    async fn test_start_unknown_validator_group() {
+        let working_dir = tempdir().unwrap();
+        let runner =
+            Arc::new(replay_runner(NODE_PORTS_OUTPUT).with_stdout("cctl-infra-net-stop", ""));
+
+        let mut network = replayed_network(runner, working_dir.path()).await;
+
+        assert!(matches!(
+            network.start_validator_group(2, Duration::ZERO).await,
+            Err(CctlError::ValidatorGroupNotFound {
+                validator_group_id: 2
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_status() {
+        let working_dir = tempdir().unwrap();
+        let runner = Arc::new(
+            replay_runner(NODE_PORTS_OUTPUT)
+                .with_stdout(
+                    "cctl-infra-net-status",
+                    r#"
+                    validator-group-1:cctl-node-1            STOPPED   Sep 02 09:49 AM
+                    validator-group-1:cctl-node-1-sidecar    RUNNING   pid 626096, uptime 0:03:03
+                    validator-group-3:cctl-node-2            RUNNING   pid 626097, uptime 0:01:12
+                    validator-group-3:cctl-node-2-sidecar    STOPPED   Not started
+                    "#,
+                )
+                .with_stdout("cctl-infra-net-stop", ""),
+        );
+
+        let mut network = replayed_network(runner, working_dir.path()).await;
+        network.refresh_status().await.unwrap();
+
+        let node_states = network
+            .casper_nodes
+            .iter()
+            .map(|node| node.state)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![NodeState::Stopped, NodeState::Running], node_states);
+        assert_eq!(
+            ProcessInfo::Description("Sep 02 09:49 AM".to_string()),
+            network.casper_nodes[0].process
+        );
+        assert_eq!(
+            ProcessInfo::Running {
+                pid: 626097,
+                uptime: Duration::from_secs(72)
+            },
+            network.casper_nodes[1].process
+        );
+        let sidecar_states = network
+            .casper_sidecars
+            .iter()
+            .map(|sidecar| sidecar.state)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![NodeState::Running, NodeState::Stopped], sidecar_states);
+    }
+
+    #[tokio::test]
+    async fn test_start_unknown_sidecar() {
+        let working_dir = tempdir().unwrap();
+        let runner =
+            Arc::new(replay_runner(NODE_PORTS_OUTPUT).with_stdout("cctl-infra-net-stop", ""));
+
+        let mut network = replayed_network(runner.clone(), working_dir.path()).await;
+
+        assert!(matches!(
+            network.start_sidecar(3).await,
+            Err(CctlError::SidecarNotFound { node_id: 3 })
+        ));
+        assert!(!runner
+            .invocations()
+            .iter()
+            .any(|invocation| invocation.starts_with("cctl-infra-sidecar-start")));
+    }
+
+    #[tokio::test]
+    async fn test_get_contract_hash_for() {
+        let working_dir = tempdir().unwrap();
+        let runner =
+            Arc::new(replay_runner(NODE_PORTS_OUTPUT).with_stdout("cctl-infra-net-stop", ""));
+        let network = replayed_network(runner, working_dir.path()).await;
+        let contracts_dir = working_dir.path().join("contracts");
+        fs::create_dir_all(&contracts_dir).unwrap();
+        fs::write(contracts_dir.join("contract-hash"), "2a".repeat(32)).unwrap();
+        fs::write(contracts_dir.join("invalid-hash"), "2a").unwrap();
+
+        assert_eq!(
+            ContractHash::new([42; 32]),
+            network.get_contract_hash_for("contract-hash").unwrap()
+        );
+        assert!(matches!(
+            network.get_contract_hash_for("invalid-hash"),
+            Err(CctlError::UnexpectedResponse(_))
+        ));
+        assert!(matches!(
+            network.get_contract_hash_for("missing-hash"),
+            Err(CctlError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_deployable_contract_from_str() {
+        let contract = DeployableContract::from_str(
+            r#"{"hash_name":"contract-hash","runtime_args":null,"path":"/contract.wasm"}"#,
+        )
+        .unwrap();
+        assert_eq!(None, contract.deployer);
+
+        let contract = DeployableContract::from_str(
+            r#"{"hash_name":"contract-hash","runtime_args":null,"path":"/contract.wasm","deployer":{"user":2}}"#,
+        )
+        .unwrap();
+        assert_eq!(Some(Deployer::User(2)), contract.deployer);
+
+        let contract = DeployableContract::from_str(
+            r#"{"hash_name":"contract-hash","runtime_args":null,"path":"/contract.wasm","deployer":{"secret_key":"/secret_key.pem"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Some(Deployer::SecretKey(PathBuf::from("/secret_key.pem"))),
+            contract.deployer
+        );
+    }
 }
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::chainspec::{apply_values, read_toml, write_toml};
+use super::CctlError;
+
+/// The format of a node's log output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Json,
+    Text,
+}
+
+/// Changes to the casper node config.toml files cctl generates for every node.
+///
+/// Values are addressed by their dotted TOML key, e.g. `logging.format`, and have to exist in
+/// the generated configs, so typos are reported instead of being ignored.
+///
+/// ```
+/// use cctl::{LogFormat, NodeConfigOverrides};
+///
+/// let overrides = NodeConfigOverrides::new()
+///     .with_log_format(LogFormat::Text)
+///     .with_value("network.max_outgoing_byte_rate_non_validators", 0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeConfigOverrides {
+    values: Vec<(String, toml::Value)>,
+}
+
+impl NodeConfigOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value at the dotted TOML key, later overrides of the same key take precedence
+    pub fn with_value(mut self, key: impl Into<String>, value: impl Into<toml::Value>) -> Self {
+        self.values.push((key.into(), value.into()));
+        self
+    }
+
+    /// `logging.format`
+    pub fn with_log_format(self, log_format: LogFormat) -> Self {
+        let log_format = match log_format {
+            LogFormat::Json => "json",
+            LogFormat::Text => "text",
+        };
+        self.with_value("logging.format", log_format)
+    }
+
+    /// `binary_port_server.qps_limit`, which limits the requests the sidecar's RPC can forward
+    pub fn with_binary_port_qps_limit(self, qps_limit: u32) -> Self {
+        self.with_value("binary_port_server.qps_limit", qps_limit)
+    }
+
+    /// `event_stream_server.event_stream_buffer_length`, the number of past events the SSE
+    /// server keeps to replay to clients
+    pub fn with_event_stream_buffer_length(self, buffer_length: u32) -> Self {
+        self.with_value(
+            "event_stream_server.event_stream_buffer_length",
+            buffer_length,
+        )
+    }
+
+    /// `diagnostics_port.enabled`
+    pub fn with_diagnostics_port(self, enabled: bool) -> Self {
+        self.with_value("diagnostics_port.enabled", enabled)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub(crate) fn extend(&mut self, other: NodeConfigOverrides) {
+        self.values.extend(other.values);
+    }
+}
+
+/// Applies the overrides for all nodes and then those for individual nodes to every
+/// config.toml of the nodes cctl set up
+pub(crate) fn apply_node_config_overrides(
+    assets_dir: &Path,
+    overrides: &NodeConfigOverrides,
+    node_overrides: &BTreeMap<u8, NodeConfigOverrides>,
+) -> Result<(), CctlError> {
+    let node_ids = node_ids(assets_dir)?;
+    if let Some(&node_id) = node_overrides
+        .keys()
+        .find(|node_id| !node_ids.contains(node_id))
+    {
+        return Err(CctlError::NodeNotFound { node_id });
+    }
+    for node_id in node_ids {
+        let node_overrides = node_overrides.get(&node_id);
+        if overrides.is_empty() && node_overrides.is_none() {
+            continue;
+        }
+        for config_path in node_config_paths(assets_dir, node_id)? {
+            tracing::info!(
+                "Applying node config overrides to {}",
+                config_path.display()
+            );
+            let mut config = read_toml(&config_path)?;
+            apply_values(&mut config, &overrides.values, "node config")?;
+            if let Some(node_overrides) = node_overrides {
+                apply_values(&mut config, &node_overrides.values, "node config")?;
+            }
+            write_toml(&config_path, &config)?;
+        }
+    }
+    Ok(())
+}
+
+fn nodes_dir(assets_dir: &Path) -> PathBuf {
+    assets_dir.join("nodes")
+}
+
+/// The ids of all nodes in the assets directory, in ascending order
+fn node_ids(assets_dir: &Path) -> Result<Vec<u8>, CctlError> {
+    let mut node_ids = fs::read_dir(nodes_dir(assets_dir))?
+        .map(|entry| Ok(entry?.file_name()))
+        .collect::<Result<Vec<_>, CctlError>>()?
+        .into_iter()
+        .filter_map(|name| name.to_str()?.strip_prefix("node-")?.parse().ok())
+        .collect::<Vec<u8>>();
+    node_ids.sort_unstable();
+    Ok(node_ids)
+}
+
+/// The config.toml files of a node, cctl generates one per protocol version
+fn node_config_paths(assets_dir: &Path, node_id: u8) -> Result<Vec<PathBuf>, CctlError> {
+    Ok(protocol_version_dirs(assets_dir, node_id)?
+        .into_iter()
+        .map(|config_dir| config_dir.join("config.toml"))
+        .filter(|config_path| config_path.is_file())
+        .collect())
+}
+
+/// The per protocol version config directories of all nodes
+pub(crate) fn node_config_dirs(assets_dir: &Path) -> Result<Vec<PathBuf>, CctlError> {
+    let mut config_dirs = Vec::new();
+    for node_id in node_ids(assets_dir)? {
+        config_dirs.extend(protocol_version_dirs(assets_dir, node_id)?);
+    }
+    Ok(config_dirs)
+}
+
+fn protocol_version_dirs(assets_dir: &Path, node_id: u8) -> Result<Vec<PathBuf>, CctlError> {
+    let config_dir = nodes_dir(assets_dir).join(format!("node-{node_id}/config"));
+    let mut protocol_version_dirs = fs::read_dir(config_dir)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>, CctlError>>()?
+        .into_iter()
+        .filter(|path| path.is_dir())
+        .collect::<Vec<_>>();
+    protocol_version_dirs.sort();
+    Ok(protocol_version_dirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const CONFIG: &str = "[logging]\nformat = 'json'\n\n[diagnostics_port]\nenabled = false\n";
+
+    #[test]
+    fn test_apply_node_config_overrides() {
+        let assets_dir = tempdir().unwrap();
+        for node_id in [1, 2] {
+            let config_dir = assets_dir
+                .path()
+                .join(format!("nodes/node-{node_id}/config/2_0_0"));
+            fs::create_dir_all(&config_dir).unwrap();
+            fs::write(config_dir.join("config.toml"), CONFIG).unwrap();
+        }
+
+        apply_node_config_overrides(
+            assets_dir.path(),
+            &NodeConfigOverrides::new().with_log_format(LogFormat::Text),
+            &BTreeMap::from([(2, NodeConfigOverrides::new().with_diagnostics_port(true))]),
+        )
+        .unwrap();
+
+        let read_config = |node_id: u8| {
+            read_toml(
+                &assets_dir
+                    .path()
+                    .join(format!("nodes/node-{node_id}/config/2_0_0/config.toml")),
+            )
+            .unwrap()
+        };
+        let expected_node_1: toml::Table =
+            "[logging]\nformat = 'text'\n\n[diagnostics_port]\nenabled = false\n"
+                .parse()
+                .unwrap();
+        let expected_node_2: toml::Table =
+            "[logging]\nformat = 'text'\n\n[diagnostics_port]\nenabled = true\n"
+                .parse()
+                .unwrap();
+        assert_eq!(expected_node_1, read_config(1));
+        assert_eq!(expected_node_2, read_config(2));
+    }
+
+    #[test]
+    fn test_apply_node_config_overrides_to_unknown_node() {
+        let assets_dir = tempdir().unwrap();
+        fs::create_dir_all(assets_dir.path().join("nodes/node-1/config/2_0_0")).unwrap();
+
+        assert!(matches!(
+            apply_node_config_overrides(
+                assets_dir.path(),
+                &NodeConfigOverrides::new(),
+                &BTreeMap::from([(3, NodeConfigOverrides::new().with_diagnostics_port(true))]),
+            ),
+            Err(CctlError::NodeNotFound { node_id: 3 })
+        ));
+    }
+}
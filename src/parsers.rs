@@ -1,30 +1,73 @@
-use super::{CasperNodePorts, CasperSidecarPorts, NodeState};
+use super::{CasperNodePorts, CasperSidecarPorts, NodeState, ProcessInfo};
 
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{line_ending, multispace0, not_line_ending, space1},
-    combinator::map,
+    character::complete::{line_ending, multispace0, not_line_ending, space0, space1},
+    combinator::{map, opt},
     multi::separated_list0,
-    sequence::tuple,
+    sequence::{terminated, tuple},
     IResult,
 };
+use std::time::Duration;
 
 pub fn parse_node_state(input: &str) -> IResult<&str, NodeState> {
     alt((
         map(tag("RUNNING"), |_| NodeState::Running),
         map(tag("STOPPED"), |_| NodeState::Stopped),
+        map(tag("STARTING"), |_| NodeState::Starting),
+        map(tag("BACKOFF"), |_| NodeState::Backoff),
+        map(tag("STOPPING"), |_| NodeState::Stopping),
+        map(tag("EXITED"), |_| NodeState::Exited),
+        map(tag("FATAL"), |_| NodeState::Fatal),
+        map(tag("UNKNOWN"), |_| NodeState::Unknown),
     ))(input)
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+/// Parses a supervisord uptime like `0:00:03` or `2 days, 1:02:03`
+pub fn parse_uptime(input: &str) -> IResult<&str, Duration> {
+    let (remainder, (days, hours, _, minutes, _, seconds)) = tuple((
+        opt(terminated(
+            nom::character::complete::u64,
+            tuple((space1, alt((tag("days"), tag("day"))), tag(","), space1)),
+        )),
+        nom::character::complete::u64,
+        tag(":"),
+        nom::character::complete::u64,
+        tag(":"),
+        nom::character::complete::u64,
+    ))(input)?;
+    let seconds = ((days.unwrap_or(0) * 24 + hours) * 60 + minutes) * 60 + seconds;
+    Ok((remainder, Duration::from_secs(seconds)))
+}
+
+/// Parses the description supervisord prints after a process' state
+pub fn parse_process_info(input: &str) -> IResult<&str, ProcessInfo> {
+    alt((
+        map(
+            tuple((
+                tag("pid "),
+                nom::character::complete::u32,
+                tag(", uptime "),
+                parse_uptime,
+                space0,
+            )),
+            |(_, pid, _, uptime, _)| ProcessInfo::Running { pid, uptime },
+        ),
+        map(not_line_ending, |description: &str| {
+            ProcessInfo::Description(description.trim().to_string())
+        }),
+    ))(input)
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum RawNodeType {
-    CasperNode(u8, u8, NodeState),
-    CasperSidecar(u8, u8, NodeState),
+    CasperNode(u8, u8, NodeState, ProcessInfo),
+    CasperSidecar(u8, u8, NodeState, ProcessInfo),
 }
 
 pub fn parse_node_line(input: &str) -> IResult<&str, RawNodeType> {
-    let (remainder, (_, _, group_id, _, node_id, _, status, _)) = tuple((
+    let (remainder, (_, _, group_id, _, node_id, _, status, _, process)) = tuple((
         multispace0,
         tag("validator-group-"),
         nom::character::complete::u8,
@@ -32,17 +75,18 @@ pub fn parse_node_line(input: &str) -> IResult<&str, RawNodeType> {
         nom::character::complete::u8,
         space1,
         parse_node_state,
-        not_line_ending,
+        space0,
+        parse_process_info,
     ))(input)?;
 
     Ok((
         remainder,
-        RawNodeType::CasperNode(group_id, node_id, status),
+        RawNodeType::CasperNode(group_id, node_id, status, process),
     ))
 }
 
 pub fn parse_sidecar_line(input: &str) -> IResult<&str, RawNodeType> {
-    let (remainder, (_, _, group_id, _, node_id, _, _, status, _)) = tuple((
+    let (remainder, (_, _, group_id, _, node_id, _, _, status, _, process)) = tuple((
         multispace0,
         tag("validator-group-"),
         nom::character::complete::u8,
@@ -51,12 +95,13 @@ pub fn parse_sidecar_line(input: &str) -> IResult<&str, RawNodeType> {
         tag("-sidecar"),
         space1,
         parse_node_state,
-        not_line_ending,
+        space0,
+        parse_process_info,
     ))(input)?;
 
     Ok((
         remainder,
-        RawNodeType::CasperSidecar(group_id, node_id, status),
+        RawNodeType::CasperSidecar(group_id, node_id, status, process),
     ))
 }
 
@@ -177,12 +222,23 @@ mod tests {
     use super::*;
     use anyhow::Error;
 
+    fn running(pid: u32, uptime_secs: u64) -> ProcessInfo {
+        ProcessInfo::Running {
+            pid,
+            uptime: Duration::from_secs(uptime_secs),
+        }
+    }
+
+    fn not_started() -> ProcessInfo {
+        ProcessInfo::Description("Not started".to_string())
+    }
+
     #[test]
     fn test_parse_node_line() -> Result<(), Error> {
         let input = "validator-group-1:cctl-node-1    RUNNING   pid 428229, uptime 0:09:06\n";
         let (_, parsed) = parse_node_line(input)?;
         Ok(assert_eq!(
-            RawNodeType::CasperNode(1, 1, NodeState::Running),
+            RawNodeType::CasperNode(1, 1, NodeState::Running, running(428229, 546)),
             parsed
         ))
     }
@@ -192,11 +248,59 @@ mod tests {
             "validator-group-1:cctl-node-1-sidecar    RUNNING   pid 626096, uptime 0:00:03\n";
         let (_, parsed) = parse_sidecar_line(input)?;
         Ok(assert_eq!(
-            RawNodeType::CasperSidecar(1, 1, NodeState::Running),
+            RawNodeType::CasperSidecar(1, 1, NodeState::Running, running(626096, 3)),
             parsed
         ))
     }
 
+    #[test]
+    fn test_parse_node_line_in_transitional_state() -> Result<(), Error> {
+        let input = "validator-group-2:cctl-node-4            STARTING  \n";
+        let (_, parsed) = parse_node_line(input)?;
+        assert_eq!(
+            RawNodeType::CasperNode(
+                2,
+                4,
+                NodeState::Starting,
+                ProcessInfo::Description(String::new())
+            ),
+            parsed
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_sidecar_line_with_exit_reason() -> Result<(), Error> {
+        let input = "validator-group-1:cctl-node-3-sidecar    FATAL     Exited too quickly (process log may have details)\n";
+        let (_, parsed) = parse_sidecar_line(input)?;
+        assert_eq!(
+            RawNodeType::CasperSidecar(
+                1,
+                3,
+                NodeState::Fatal,
+                ProcessInfo::Description(
+                    "Exited too quickly (process log may have details)".to_string()
+                )
+            ),
+            parsed
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_uptime() -> Result<(), Error> {
+        assert_eq!(Duration::from_secs(3), parse_uptime("0:00:03")?.1);
+        assert_eq!(
+            Duration::from_secs(86_400 + 3723),
+            parse_uptime("1 day, 1:02:03")?.1
+        );
+        assert_eq!(
+            Duration::from_secs(2 * 86_400 + 3723),
+            parse_uptime("2 days, 1:02:03")?.1
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_parse_cctl_infra_net_start_lines() -> Result<(), Error> {
         let input = r#"
@@ -232,26 +336,26 @@ mod tests {
         "#;
         let (_, parsed) = parse_cctl_infra_net_start_lines(input)?;
         let expected = vec![
-            RawNodeType::CasperNode(1, 1, NodeState::Running),
-            RawNodeType::CasperSidecar(1, 1, NodeState::Running),
-            RawNodeType::CasperNode(1, 2, NodeState::Running),
-            RawNodeType::CasperSidecar(1, 2, NodeState::Running),
-            RawNodeType::CasperNode(1, 3, NodeState::Running),
-            RawNodeType::CasperSidecar(1, 3, NodeState::Running),
-            RawNodeType::CasperNode(2, 4, NodeState::Running),
-            RawNodeType::CasperSidecar(2, 4, NodeState::Running),
-            RawNodeType::CasperNode(2, 5, NodeState::Running),
-            RawNodeType::CasperSidecar(2, 5, NodeState::Running),
-            RawNodeType::CasperNode(3, 10, NodeState::Stopped),
-            RawNodeType::CasperSidecar(3, 10, NodeState::Stopped),
-            RawNodeType::CasperNode(3, 6, NodeState::Stopped),
-            RawNodeType::CasperSidecar(3, 6, NodeState::Stopped),
-            RawNodeType::CasperNode(3, 7, NodeState::Stopped),
-            RawNodeType::CasperSidecar(3, 7, NodeState::Stopped),
-            RawNodeType::CasperNode(3, 8, NodeState::Stopped),
-            RawNodeType::CasperSidecar(3, 8, NodeState::Stopped),
-            RawNodeType::CasperNode(3, 9, NodeState::Stopped),
-            RawNodeType::CasperSidecar(3, 9, NodeState::Stopped),
+            RawNodeType::CasperNode(1, 1, NodeState::Running, running(626095, 3)),
+            RawNodeType::CasperSidecar(1, 1, NodeState::Running, running(626096, 3)),
+            RawNodeType::CasperNode(1, 2, NodeState::Running, running(626097, 3)),
+            RawNodeType::CasperSidecar(1, 2, NodeState::Running, running(626098, 3)),
+            RawNodeType::CasperNode(1, 3, NodeState::Running, running(626101, 3)),
+            RawNodeType::CasperSidecar(1, 3, NodeState::Running, running(626102, 3)),
+            RawNodeType::CasperNode(2, 4, NodeState::Running, running(626285, 2)),
+            RawNodeType::CasperSidecar(2, 4, NodeState::Running, running(626286, 2)),
+            RawNodeType::CasperNode(2, 5, NodeState::Running, running(626287, 2)),
+            RawNodeType::CasperSidecar(2, 5, NodeState::Running, running(626288, 2)),
+            RawNodeType::CasperNode(3, 10, NodeState::Stopped, not_started()),
+            RawNodeType::CasperSidecar(3, 10, NodeState::Stopped, not_started()),
+            RawNodeType::CasperNode(3, 6, NodeState::Stopped, not_started()),
+            RawNodeType::CasperSidecar(3, 6, NodeState::Stopped, not_started()),
+            RawNodeType::CasperNode(3, 7, NodeState::Stopped, not_started()),
+            RawNodeType::CasperSidecar(3, 7, NodeState::Stopped, not_started()),
+            RawNodeType::CasperNode(3, 8, NodeState::Stopped, not_started()),
+            RawNodeType::CasperSidecar(3, 8, NodeState::Stopped, not_started()),
+            RawNodeType::CasperNode(3, 9, NodeState::Stopped, not_started()),
+            RawNodeType::CasperSidecar(3, 9, NodeState::Stopped, not_started()),
         ];
         Ok(assert_eq!(expected, parsed))
     }
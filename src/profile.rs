@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use super::{ChainspecOverrides, Topology};
+
+/// Presets of chainspec values and topology, the network's other configuration is applied
+/// on top of them and takes precedence
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NetworkProfile {
+    /// cctl's defaults, which resemble a real network's timings
+    #[default]
+    Standard,
+    /// A single node producing blocks and switching eras as fast as it reliably can,
+    /// e.g. for integration test suites
+    Fast,
+}
+
+impl NetworkProfile {
+    pub(crate) fn chainspec_overrides(self) -> ChainspecOverrides {
+        match self {
+            NetworkProfile::Standard => ChainspecOverrides::new(),
+            NetworkProfile::Fast => ChainspecOverrides::new()
+                .with_minimum_block_time(Duration::from_secs(1))
+                .with_era_duration(Duration::from_secs(10))
+                .with_minimum_era_height(5),
+        }
+    }
+
+    pub(crate) fn topology(self) -> Option<Topology> {
+        match self {
+            NetworkProfile::Standard => None,
+            NetworkProfile::Fast => Some(Topology::genesis_only(1)),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use serde::Deserialize;
+
+use super::CctlError;
+
+/// The state of a node's reactor as reported by its REST `/status` endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ReactorState {
+    Initialize,
+    CatchUp,
+    Upgrading,
+    KeepUp,
+    /// The node participates in consensus, which only validators do
+    Validate,
+    ShutdownForUpgrade,
+    ShutdownAfterCatchingUp,
+    /// A state introduced by a newer casper-node version
+    #[serde(other)]
+    Unknown,
+}
+
+/// The latest block a node added to its chain
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LastAddedBlockInfo {
+    pub hash: String,
+    pub timestamp: String,
+    pub era_id: u64,
+    pub height: u64,
+    pub state_root_hash: String,
+}
+
+/// The subset of a node's REST `/status` response cctl makes use of
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NodeStatus {
+    pub chainspec_name: String,
+    pub reactor_state: ReactorState,
+    /// `None` until the node added its first block
+    pub last_added_block_info: Option<LastAddedBlockInfo>,
+    pub build_version: String,
+    pub uptime: String,
+}
+
+/// Fetches the status of the node with the given REST base URL
+pub(crate) async fn get_node_status(rest_url: &str) -> Result<NodeStatus, CctlError> {
+    Ok(reqwest::get(format!("{rest_url}/status"))
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_node_status() {
+        let status = r#"{
+            "api_version": "2.0.0",
+            "peers": [],
+            "build_version": "2.0.0-1a2b3c4d",
+            "chainspec_name": "cspr-dev-cctl",
+            "starting_state_root_hash": "0000000000000000000000000000000000000000000000000000000000000000",
+            "last_added_block_info": {
+                "hash": "9d3a7b4d6c4f0ae0d5f3a3b8c0e7b4f1e2d3c4b5a69788796a5b4c3d2e1f0a1b",
+                "timestamp": "2024-09-02T09:49:32.804Z",
+                "era_id": 3,
+                "height": 42,
+                "state_root_hash": "1d3a7b4d6c4f0ae0d5f3a3b8c0e7b4f1e2d3c4b5a69788796a5b4c3d2e1f0a1b",
+                "creator": "01cbd4b58a3e8ee7bba4bfb1bd8f3ab1dfd3f83b9f03f8e2d3c9a0b1c2d3e4f5a6"
+            },
+            "our_public_signing_key": null,
+            "round_length": "4s 96ms",
+            "next_upgrade": null,
+            "uptime": "2m 40s",
+            "reactor_state": "Validate",
+            "last_progress": "2024-09-02T09:47:00.000Z",
+            "available_block_range": { "low": 0, "high": 42 },
+            "block_sync": { "historical": null, "forward": null },
+            "latest_switch_block_hash": null
+        }"#;
+
+        let status: NodeStatus = serde_json::from_str(status).unwrap();
+        assert_eq!(ReactorState::Validate, status.reactor_state);
+        assert_eq!(
+            Some(42),
+            status.last_added_block_info.map(|info| info.height)
+        );
+    }
+
+    #[test]
+    fn test_deserialize_unknown_reactor_state() {
+        let state: ReactorState = serde_json::from_str(r#""Sleep""#).unwrap();
+        assert_eq!(ReactorState::Unknown, state);
+    }
+}
@@ -0,0 +1,242 @@
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::process::Command;
+
+use super::CctlError;
+
+/// The captured result of a cctl script invocation
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptOutput {
+    /// The script, its arguments and additional environment variables as they were invoked
+    pub command: String,
+    /// The value of `CCTL_ASSETS` the script was invoked with
+    pub assets_dir: PathBuf,
+    /// `None` if the script was terminated by a signal
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Executes the cctl scripts a [`CCTLNetwork`](super::CCTLNetwork) is driven by.
+///
+/// Implementations only capture the output, checking the exit code, logging and enforcing
+/// timeouts is done by the caller.
+#[async_trait]
+pub trait CctlCommandRunner: Send + Sync {
+    /// Runs `script` with `args`, `CCTL_ASSETS` set to `assets_dir` and the additional
+    /// environment variables `env`
+    async fn run(
+        &self,
+        assets_dir: &Path,
+        env: &[(String, String)],
+        script: &str,
+        args: &[String],
+    ) -> Result<ScriptOutput, CctlError>;
+}
+
+/// Runs the cctl scripts found on the `PATH`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShellCommandRunner;
+
+#[async_trait]
+impl CctlCommandRunner for ShellCommandRunner {
+    async fn run(
+        &self,
+        assets_dir: &Path,
+        env: &[(String, String)],
+        script: &str,
+        args: &[String],
+    ) -> Result<ScriptOutput, CctlError> {
+        let output = Command::new(script)
+            .env("CCTL_ASSETS", assets_dir)
+            .envs(env.iter().map(|(key, value)| (key, value)))
+            .args(args)
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::NotFound => CctlError::ScriptNotFound {
+                    script: script.to_string(),
+                },
+                _ => CctlError::Io(err),
+            })?;
+        let non_utf8_output = |_| CctlError::NonUtf8Output {
+            script: script.to_string(),
+        };
+        Ok(ScriptOutput {
+            command: render_command(env, script, args),
+            assets_dir: assets_dir.to_path_buf(),
+            exit_code: output.status.code(),
+            stdout: String::from_utf8(output.stdout).map_err(non_utf8_output)?,
+            stderr: String::from_utf8(output.stderr).map_err(non_utf8_output)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RecordedOutput {
+    exit_code: Option<i32>,
+    stdout: String,
+    stderr: String,
+}
+
+/// Replays recorded script outputs instead of running cctl, e.g. to test a network's
+/// lifecycle without a cctl installation.
+///
+/// The outputs recorded for a script are replayed in order, the last one is repeated once
+/// all others were replayed. Scripts without any recorded output fail with
+/// [`CctlError::ScriptNotFound`].
+#[derive(Debug, Default)]
+pub struct ReplayCommandRunner {
+    outputs: Mutex<HashMap<String, VecDeque<RecordedOutput>>>,
+    invocations: Mutex<Vec<String>>,
+}
+
+impl ReplayCommandRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a successful run of `script` that printed `stdout`
+    pub fn with_stdout(self, script: &str, stdout: impl Into<String>) -> Self {
+        self.with_output(script, Some(0), stdout, "")
+    }
+
+    /// Records a run of `script` that exited with `exit_code`
+    pub fn with_output(
+        self,
+        script: &str,
+        exit_code: Option<i32>,
+        stdout: impl Into<String>,
+        stderr: impl Into<String>,
+    ) -> Self {
+        self.outputs
+            .lock()
+            .unwrap()
+            .entry(script.to_string())
+            .or_default()
+            .push_back(RecordedOutput {
+                exit_code,
+                stdout: stdout.into(),
+                stderr: stderr.into(),
+            });
+        self
+    }
+
+    /// The commands that were run so far, in order
+    pub fn invocations(&self) -> Vec<String> {
+        self.invocations.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl CctlCommandRunner for ReplayCommandRunner {
+    async fn run(
+        &self,
+        assets_dir: &Path,
+        env: &[(String, String)],
+        script: &str,
+        args: &[String],
+    ) -> Result<ScriptOutput, CctlError> {
+        let command = render_command(env, script, args);
+        self.invocations.lock().unwrap().push(command.clone());
+
+        let mut outputs = self.outputs.lock().unwrap();
+        let recorded = outputs
+            .get_mut(script)
+            .and_then(|recorded| match recorded.len() {
+                0 | 1 => recorded.front().cloned(),
+                _ => recorded.pop_front(),
+            })
+            .ok_or_else(|| CctlError::ScriptNotFound {
+                script: script.to_string(),
+            })?;
+        Ok(ScriptOutput {
+            command,
+            assets_dir: assets_dir.to_path_buf(),
+            exit_code: recorded.exit_code,
+            stdout: recorded.stdout,
+            stderr: recorded.stderr,
+        })
+    }
+}
+
+fn render_command(env: &[(String, String)], script: &str, args: &[String]) -> String {
+    env.iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .chain(std::iter::once(script.to_string()))
+        .chain(args.iter().cloned())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Runs a cctl script against the given assets directory and captures its output.
+///
+/// Fails with [`CctlError::ScriptFailed`] if the script exits unsuccessfully, and with
+/// [`CctlError::Timeout`] if it doesn't exit within `timeout`.
+/// Dropping the returned future cancels the script, the [`ShellCommandRunner`] kills it.
+pub(crate) async fn run_script(
+    runner: &dyn CctlCommandRunner,
+    assets_dir: &Path,
+    env: &[(String, String)],
+    script: &str,
+    args: &[String],
+    timeout: Duration,
+) -> Result<ScriptOutput, CctlError> {
+    let command = render_command(env, script, args);
+    tracing::debug!("Running 'CCTL_ASSETS={} {command}'", assets_dir.display());
+
+    let output = tokio::time::timeout(timeout, runner.run(assets_dir, env, script, args))
+        .await
+        .map_err(|_| CctlError::Timeout {
+            operation: format!("running '{command}'"),
+            elapsed: timeout,
+        })??;
+    tracing::info!("{}", output.stdout);
+
+    if output.exit_code != Some(0) {
+        tracing::error!(
+            "'{}' failed with exit code {:?}: {}",
+            output.command,
+            output.exit_code,
+            output.stderr
+        );
+        return Err(CctlError::ScriptFailed {
+            script: output.command,
+            exit_code: output.exit_code,
+            stderr: output.stderr,
+        });
+    }
+    if !output.stderr.is_empty() {
+        tracing::debug!("{}", output.stderr);
+    }
+    Ok(output)
+}
+
+/// Drives a future to completion from synchronous code, e.g. `Drop` implementations.
+///
+/// The future runs on a dedicated thread with its own runtime, so unlike
+/// `Handle::block_on` this doesn't panic when called from within an async context.
+pub(crate) fn block_on<F>(future: F) -> F::Output
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("Failed to build a runtime")
+                    .block_on(future)
+            })
+            .join()
+            .expect("The blocking task panicked")
+    })
+}
@@ -0,0 +1,384 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use casper_types::{
+    execution::{Effects, ExecutionResult},
+    Block, BlockHash, EraId, FinalitySignature, PublicKey, Timestamp, Transaction, TransactionHash,
+};
+use futures_util::stream::{self, Stream};
+use serde::Deserialize;
+
+use super::CctlError;
+
+/// The time to wait before reconnecting to a node's event stream
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+/// The number of consecutive failed connection attempts after which the event stream ends
+const MAX_FAILED_CONNECTIONS: u32 = 10;
+
+/// An event emitted by a node's SSE server
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent {
+    /// The event's position in the node's event stream, `None` for the `ApiVersion` event
+    /// at the start of the stream
+    pub id: Option<u32>,
+    pub data: EventData,
+}
+
+/// The payload of an [`SseEvent`]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum EventData {
+    ApiVersion(String),
+    BlockAdded {
+        block_hash: BlockHash,
+        block: Box<Block>,
+    },
+    TransactionAccepted(Box<Transaction>),
+    TransactionProcessed {
+        transaction_hash: TransactionHash,
+        block_hash: BlockHash,
+        execution_result: Box<ExecutionResult>,
+    },
+    TransactionExpired {
+        transaction_hash: TransactionHash,
+    },
+    FinalitySignature(Box<FinalitySignature>),
+    Step {
+        era_id: EraId,
+        execution_effects: Effects,
+    },
+    Fault {
+        era_id: EraId,
+        public_key: PublicKey,
+        timestamp: Timestamp,
+    },
+    Shutdown,
+    /// An event this version of cctl doesn't know about, kept as JSON
+    #[serde(skip)]
+    Other(serde_json::Value),
+}
+
+impl EventData {
+    const KNOWN_EVENTS: &'static [&'static str] = &[
+        "ApiVersion",
+        "BlockAdded",
+        "TransactionAccepted",
+        "TransactionProcessed",
+        "TransactionExpired",
+        "FinalitySignature",
+        "Step",
+        "Fault",
+        "Shutdown",
+    ];
+
+    fn parse(data: &str) -> Result<Self, CctlError> {
+        let invalid_event = |err: serde_json::Error| {
+            CctlError::UnexpectedResponse(format!("invalid event '{data}': {err}"))
+        };
+        let value: serde_json::Value = serde_json::from_str(data).map_err(invalid_event)?;
+        let name = match &value {
+            serde_json::Value::String(name) => Some(name.as_str()),
+            serde_json::Value::Object(event) => event.keys().next().map(String::as_str),
+            _ => None,
+        };
+        match name {
+            Some(name) if Self::KNOWN_EVENTS.contains(&name) => {
+                serde_json::from_value(value).map_err(invalid_event)
+            }
+            _ => Ok(EventData::Other(value)),
+        }
+    }
+}
+
+/// A `data` field and its `id` as received from the server, before parsing the data
+#[derive(Debug, PartialEq)]
+struct RawEvent {
+    id: Option<u32>,
+    data: String,
+}
+
+/// Splits the bytes received from an SSE server into events
+#[derive(Debug, Default)]
+struct SseParser {
+    /// An incomplete line received so far
+    line: Vec<u8>,
+    data: Vec<String>,
+    id: Option<u32>,
+}
+
+impl SseParser {
+    /// Feeds the next chunk received, returning the events it completed
+    fn push(&mut self, chunk: &[u8]) -> Vec<RawEvent> {
+        let mut events = Vec::new();
+        for &byte in chunk {
+            if byte != b'\n' {
+                self.line.push(byte);
+                continue;
+            }
+            let line = String::from_utf8_lossy(&self.line).into_owned();
+            self.line.clear();
+            let line = line.strip_suffix('\r').unwrap_or(&line);
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(RawEvent {
+                        id: self.id.take(),
+                        data: self.data.join("\n"),
+                    });
+                    self.data.clear();
+                }
+                continue;
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "data" => self.data.push(value.to_string()),
+                "id" => self.id = value.parse().ok(),
+                // Comments, i.e. keep-alives, and event types aren't used by casper nodes
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+struct EventStreamState {
+    events_url: String,
+    start_from: Option<u32>,
+    last_id: Option<u32>,
+    response: Option<reqwest::Response>,
+    parser: SseParser,
+    pending: VecDeque<Result<SseEvent, CctlError>>,
+    /// Whether a connection was established before, after which the `ApiVersion` event
+    /// repeated by the node at the start of every connection is dropped
+    reconnected: bool,
+    failed_connections: u32,
+    done: bool,
+}
+
+impl EventStreamState {
+    async fn next_event(&mut self) -> Result<SseEvent, CctlError> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return event;
+            }
+            let Some(response) = &mut self.response else {
+                self.connect().await?;
+                continue;
+            };
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    for RawEvent { id, data } in self.parser.push(&chunk) {
+                        if id.is_some() {
+                            self.last_id = id;
+                        }
+                        let event = EventData::parse(&data).map(|data| SseEvent { id, data });
+                        if self.reconnected
+                            && matches!(
+                                event,
+                                Ok(SseEvent {
+                                    data: EventData::ApiVersion(_),
+                                    ..
+                                })
+                            )
+                        {
+                            continue;
+                        }
+                        self.pending.push_back(event);
+                    }
+                }
+                Ok(None) => {
+                    tracing::info!("Event stream {} ended, reconnecting", self.events_url);
+                    self.response = None;
+                    self.reconnected = true;
+                }
+                Err(err) => {
+                    tracing::info!(
+                        "Event stream {} failed, reconnecting: {err}",
+                        self.events_url
+                    );
+                    self.response = None;
+                    self.reconnected = true;
+                }
+            }
+        }
+    }
+
+    /// Connects to the event stream, resuming after the last event received
+    async fn connect(&mut self) -> Result<(), CctlError> {
+        if self.failed_connections > 0 || self.last_id.is_some() {
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+        let start_from = self.last_id.map(|id| id + 1).or(self.start_from);
+        let url = match start_from {
+            Some(start_from) => format!("{}?start_from={start_from}", self.events_url),
+            None => self.events_url.clone(),
+        };
+        tracing::debug!("Connecting to {url}");
+        match reqwest::get(&url)
+            .await
+            .and_then(reqwest::Response::error_for_status)
+        {
+            Ok(response) => {
+                self.response = Some(response);
+                self.parser = SseParser::default();
+                self.failed_connections = 0;
+                Ok(())
+            }
+            Err(err) => {
+                self.failed_connections += 1;
+                tracing::debug!("Failed to connect to {url}: {err}");
+                if self.failed_connections >= MAX_FAILED_CONNECTIONS {
+                    self.done = true;
+                    return Err(err.into());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Subscribes to the node's event stream at `events_url`, reconnecting and resuming after the
+/// last received event if the connection drops. The stream ends with an error once connecting
+/// failed repeatedly.
+pub(crate) fn subscribe(
+    events_url: String,
+    start_from: Option<u32>,
+) -> impl Stream<Item = Result<SseEvent, CctlError>> + Send {
+    let state = EventStreamState {
+        events_url,
+        start_from,
+        last_id: None,
+        response: None,
+        parser: SseParser::default(),
+        pending: VecDeque::new(),
+        reconnected: false,
+        failed_connections: 0,
+        done: false,
+    };
+    stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+        let event = state.next_event().await;
+        Some((event, state))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_events_split_across_chunks() {
+        let mut parser = SseParser::default();
+
+        assert!(parser
+            .push(b"data:{\"ApiVersion\":\"2.0.0\"}\n\n:\n\ndata:{\"Block")
+            .into_iter()
+            .eq([RawEvent {
+                id: None,
+                data: r#"{"ApiVersion":"2.0.0"}"#.to_string(),
+            }]));
+        assert!(parser.push(b"Added\":{}}\r\nid: 7\r\n").is_empty());
+        assert!(parser.push(b"\r\n").into_iter().eq([RawEvent {
+            id: Some(7),
+            data: r#"{"BlockAdded":{}}"#.to_string(),
+        }]));
+    }
+
+    #[test]
+    fn test_parse_multi_line_data() {
+        let mut parser = SseParser::default();
+
+        assert!(parser
+            .push(b"data: \"Shut\ndata:down\"\nid:3\n\n")
+            .into_iter()
+            .eq([RawEvent {
+                id: Some(3),
+                data: "\"Shut\ndown\"".to_string(),
+            }]));
+    }
+
+    #[test]
+    fn test_parse_event_data() {
+        assert_eq!(
+            EventData::Shutdown,
+            EventData::parse(r#""Shutdown""#).unwrap()
+        );
+        assert!(matches!(
+            EventData::parse(r#"{"Step":{"era_id":3,"execution_effects":[]}}"#).unwrap(),
+            EventData::Step { era_id, .. } if era_id.value() == 3
+        ));
+        assert!(matches!(
+            EventData::parse(
+                r#"{"TransactionProcessed":{"transaction_hash":{"Deploy":"aa"},"block_hash":"bb","execution_result":{"Version1":{"Success":{"effect":{"operations":[],"transforms":[]},"transfers":[],"cost":"100"}}},"ttl":"30m"}}"#
+            )
+            .unwrap(),
+            EventData::TransactionProcessed { .. }
+        ));
+        assert!(matches!(
+            EventData::parse(
+                r#"{"Fault":{"era_id":2,"public_key":"00","timestamp":"2024-09-02T09:49:32.804Z"}}"#
+            )
+            .unwrap(),
+            EventData::Fault { era_id, .. } if era_id.value() == 2
+        ));
+        assert!(EventData::parse(
+            r#"{"Fault":{"era_id":2,"public_key":"00","timestamp":1725270572804}}"#
+        )
+        .is_err());
+        assert_eq!(
+            EventData::Other(serde_json::json!({"SidecarVersion": "1.0.0"})),
+            EventData::parse(r#"{"SidecarVersion":"1.0.0"}"#).unwrap()
+        );
+        assert!(EventData::parse(r#"{"Step":{}}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_the_last_event_on_reconnect() {
+        use futures_util::StreamExt;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("0.0.0.0:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (requests_tx, mut requests) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut next_id = 4;
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0; 1024];
+                let read = stream.read(&mut request).await.unwrap();
+                let request = String::from_utf8_lossy(&request[..read]).into_owned();
+                let _ = requests_tx.send(request.lines().next().unwrap_or_default().to_string());
+                // Every connection ends after a single event
+                let body = format!(
+                    "data:{{\"ApiVersion\":\"2.0.0\"}}\n\ndata:\"Shutdown\"\nid:{next_id}\n\n"
+                );
+                next_id += 1;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        let events: Vec<_> = subscribe(format!("http://0.0.0.0:{port}/events"), Some(4))
+            .take(3)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(
+            vec![None, Some(4), Some(5)],
+            events.iter().map(|event| event.id).collect::<Vec<_>>()
+        );
+        assert_eq!(EventData::Shutdown, events[2].data);
+        assert_eq!(
+            "GET /events?start_from=4 HTTP/1.1",
+            requests.recv().await.unwrap()
+        );
+        assert_eq!(
+            "GET /events?start_from=5 HTTP/1.1",
+            requests.recv().await.unwrap()
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use super::CctlError;
+
+/// The nodes of a CCTL network and which of them start at genesis.
+///
+/// cctl assigns the nodes to validator groups by their id: group 1 are the bootstrap nodes,
+/// group 2 the remaining genesis validators, both are started with the network. Group 3 are
+/// the nodes which are set up but not started, e.g. to join the network later on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Topology {
+    /// The number of nodes that are set up, including those not started at genesis
+    pub nodes: u8,
+    /// The number of nodes which are validators at genesis, i.e. validator groups 1 and 2
+    pub genesis_nodes: u8,
+    /// The number of genesis nodes the others bootstrap from, i.e. validator group 1
+    pub bootstrap_nodes: u8,
+}
+
+impl Default for Topology {
+    /// cctl's default topology
+    fn default() -> Self {
+        Self {
+            nodes: 10,
+            genesis_nodes: 5,
+            bootstrap_nodes: 3,
+        }
+    }
+}
+
+impl Topology {
+    /// A network of `nodes` nodes which are all started at genesis
+    pub fn genesis_only(nodes: u8) -> Self {
+        Self {
+            nodes,
+            genesis_nodes: nodes,
+            bootstrap_nodes: nodes.min(Self::default().bootstrap_nodes),
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), CctlError> {
+        let Topology {
+            nodes,
+            genesis_nodes,
+            bootstrap_nodes,
+        } = *self;
+        if bootstrap_nodes == 0 || bootstrap_nodes > genesis_nodes || genesis_nodes > nodes {
+            return Err(CctlError::InvalidConfig(format!(
+                "invalid topology {self:?}, expected 0 < bootstrap_nodes <= genesis_nodes <= nodes"
+            )));
+        }
+        Ok(())
+    }
+
+    /// The topology of the nodes cctl set up, given the validator group of each node
+    pub(crate) fn from_validator_groups(validator_group_ids: impl IntoIterator<Item = u8>) -> Self {
+        let mut topology = Topology {
+            nodes: 0,
+            genesis_nodes: 0,
+            bootstrap_nodes: 0,
+        };
+        for validator_group_id in validator_group_ids {
+            topology.nodes += 1;
+            if validator_group_id <= 2 {
+                topology.genesis_nodes += 1;
+            }
+            if validator_group_id == 1 {
+                topology.bootstrap_nodes += 1;
+            }
+        }
+        topology
+    }
+
+    /// The environment variables the cctl scripts read the topology from. Since a cctl
+    /// version ignoring them would silently set up its default topology, the nodes that
+    /// were actually set up are compared with the requested topology after the start.
+    pub(crate) fn script_env(&self) -> Vec<(String, String)> {
+        vec![
+            ("CCTL_COUNT_OF_NODES".to_string(), self.nodes.to_string()),
+            (
+                "CCTL_COUNT_OF_GENESIS_NODES".to_string(),
+                self.genesis_nodes.to_string(),
+            ),
+            (
+                "CCTL_COUNT_OF_BOOTSTRAP_NODES".to_string(),
+                self.bootstrap_nodes.to_string(),
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_topology() {
+        assert!(Topology::default().validate().is_ok());
+        assert!(Topology::genesis_only(1).validate().is_ok());
+        assert!(Topology::genesis_only(0).validate().is_err());
+        assert!(Topology {
+            nodes: 4,
+            genesis_nodes: 5,
+            bootstrap_nodes: 3,
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_topology_from_validator_groups() {
+        assert_eq!(
+            Topology::default(),
+            Topology::from_validator_groups([1, 1, 1, 2, 2, 3, 3, 3, 3, 3])
+        );
+        assert_eq!(
+            Topology::genesis_only(1),
+            Topology::from_validator_groups([1])
+        );
+    }
+}
@@ -25,6 +25,7 @@ async fn test_cctl_deploys_a_contract_successfully() {
         hash_name: hash_name.to_string(),
         runtime_args: None,
         path: contract_wasm_path,
+        deployer: None,
     };
 
     let network = CCTLNetwork::run(None, Some(vec![contract_to_deploy]), None, None)
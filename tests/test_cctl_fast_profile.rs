@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use cctl::{CCTLNetwork, NetworkProfile};
+
+fn tracing_init() {
+    let _ = tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()))
+        .with(tracing_subscriber::fmt::layer())
+        .try_init();
+}
+
+#[tokio::test]
+async fn test_cctl_fast_profile() {
+    tracing_init();
+
+    let network = CCTLNetwork::builder()
+        .with_profile(NetworkProfile::Fast)
+        .run()
+        .await
+        .unwrap();
+    assert_eq!(1, network.casper_nodes.len());
+
+    // The profile's eras last 10 seconds
+    network
+        .await_next_era(Duration::from_secs(60))
+        .await
+        .unwrap();
+
+    network.shutdown().await.unwrap();
+}
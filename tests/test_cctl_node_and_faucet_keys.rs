@@ -0,0 +1,26 @@
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use cctl::CCTLNetwork;
+
+fn tracing_init() {
+    let _ = tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()))
+        .with(tracing_subscriber::fmt::layer())
+        .try_init();
+}
+
+#[tokio::test]
+async fn test_cctl_node_and_faucet_keys() {
+    tracing_init();
+
+    let network = CCTLNetwork::builder().run().await.unwrap();
+
+    for node in &network.casper_nodes {
+        let keys = node.keys().unwrap();
+        assert_eq!(keys.account_hash, keys.public_key.to_account_hash());
+    }
+    let faucet = network.faucet().unwrap();
+    assert_eq!(faucet.account_hash, faucet.public_key.to_account_hash());
+
+    network.shutdown().await.unwrap();
+}
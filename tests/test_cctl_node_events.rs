@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use cctl::{CCTLNetwork, EventData};
+
+fn tracing_init() {
+    let _ = tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()))
+        .with(tracing_subscriber::fmt::layer())
+        .try_init();
+}
+
+#[tokio::test]
+async fn test_cctl_node_events() {
+    tracing_init();
+
+    let network = CCTLNetwork::builder().run().await.unwrap();
+
+    let mut blocks_added = network.casper_nodes[0]
+        .events()
+        .filter_map(|event| async move {
+            match event.unwrap().data {
+                EventData::BlockAdded { block_hash, .. } => Some(block_hash),
+                _ => None,
+            }
+        })
+        .boxed();
+    tokio::time::timeout(Duration::from_secs(60), blocks_added.next())
+        .await
+        .unwrap()
+        .unwrap();
+
+    network.shutdown().await.unwrap();
+}
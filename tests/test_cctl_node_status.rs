@@ -0,0 +1,27 @@
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use cctl::{CCTLNetwork, NodeState, ReactorState};
+
+fn tracing_init() {
+    let _ = tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()))
+        .with(tracing_subscriber::fmt::layer())
+        .try_init();
+}
+
+#[tokio::test]
+async fn test_cctl_node_status() {
+    tracing_init();
+
+    let network = CCTLNetwork::builder().run().await.unwrap();
+
+    for node in &network.casper_nodes {
+        if node.state == NodeState::Running {
+            let status = node.status().await.unwrap();
+            assert_eq!(status.reactor_state, ReactorState::Validate);
+            assert_eq!(status.chainspec_name, network.chain_name());
+        }
+    }
+
+    network.shutdown().await.unwrap();
+}
@@ -0,0 +1,34 @@
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use cctl::{CCTLNetwork, NodeState};
+
+fn tracing_init() {
+    let _ = tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()))
+        .with(tracing_subscriber::fmt::layer())
+        .try_init();
+}
+
+#[tokio::test]
+async fn test_cctl_refresh_status() {
+    tracing_init();
+
+    let mut network = CCTLNetwork::builder().run().await.unwrap();
+
+    // Nothing crashed since the network was started
+    let running_nodes = network
+        .casper_nodes
+        .iter()
+        .filter(|node| node.state == NodeState::Running)
+        .map(|node| node.id)
+        .collect::<Vec<_>>();
+    network.refresh_status().await.unwrap();
+    for node in &network.casper_nodes {
+        assert_eq!(
+            running_nodes.contains(&node.id),
+            node.state == NodeState::Running
+        );
+    }
+
+    network.shutdown().await.unwrap();
+}
@@ -0,0 +1,27 @@
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use cctl::{CCTLNetwork, NodeState, Topology};
+
+fn tracing_init() {
+    let _ = tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()))
+        .with(tracing_subscriber::fmt::layer())
+        .try_init();
+}
+
+#[tokio::test]
+async fn test_cctl_topology() {
+    tracing_init();
+
+    let network = CCTLNetwork::builder()
+        .with_topology(Topology::genesis_only(1))
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(1, network.casper_nodes.len());
+    assert_eq!(1, network.casper_nodes[0].validator_group_id);
+    assert_eq!(NodeState::Running, network.casper_nodes[0].state);
+
+    network.shutdown().await.unwrap();
+}
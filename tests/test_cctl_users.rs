@@ -0,0 +1,26 @@
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use cctl::CCTLNetwork;
+
+fn tracing_init() {
+    let _ = tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()))
+        .with(tracing_subscriber::fmt::layer())
+        .try_init();
+}
+
+#[tokio::test]
+async fn test_cctl_users() {
+    tracing_init();
+
+    let network = CCTLNetwork::builder().run().await.unwrap();
+
+    let users = network.users().unwrap();
+    assert!(!users.is_empty());
+    assert_eq!(
+        users[0].keys.account_hash,
+        network.user(1).unwrap().keys.account_hash
+    );
+
+    network.shutdown().await.unwrap();
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use cctl::{CCTLNetwork, NodeState};
+
+fn tracing_init() {
+    let _ = tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()))
+        .with(tracing_subscriber::fmt::layer())
+        .try_init();
+}
+
+#[tokio::test]
+async fn test_cctl_validator_group_joins() {
+    tracing_init();
+
+    let mut network = CCTLNetwork::builder().run().await.unwrap();
+    // cctl doesn't start validator group 3 at genesis
+    assert!(network
+        .casper_nodes
+        .iter()
+        .filter(|node| node.validator_group_id == 3)
+        .all(|node| node.state == NodeState::Stopped));
+
+    network
+        .start_validator_group(3, Duration::from_secs(300))
+        .await
+        .unwrap();
+
+    network.refresh_status().await.unwrap();
+    assert!(network
+        .casper_nodes
+        .iter()
+        .all(|node| node.state == NodeState::Running));
+
+    network.shutdown().await.unwrap();
+}